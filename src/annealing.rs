@@ -0,0 +1,90 @@
+// Simulated annealing over the reinsertion neighborhood of the hill-climber. A move removes a
+// random vertex and reinserts it at a position drawn with probability proportional to
+// exp(-(cost - mincost) / T) over all positions (see `sifting::insert_cost_per_pos`), so worse
+// positions are taken while the temperature is high and the search turns greedy as it cools.
+use crate::sifting::insert_cost_per_pos;
+use crate::local_search::{Incumbents, SccSearch};
+use crate::strategy::Context;
+use rand::Rng;
+use std::time::Duration;
+
+/// Length of the cooling schedule if the solve has no time limit.
+const DEFAULT_BUDGET: Duration = Duration::from_secs(300);
+
+/// Ratio of the final and the initial temperature.
+const FINAL_RATIO: f64 = 1e-3;
+
+// vertices sampled to estimate the initial temperature of an SCC
+const SAMPLES: usize = 50;
+
+#[derive(Default)]
+pub struct Annealing {
+    t0: Vec<f64>,
+}
+
+impl SccSearch for Annealing {
+    fn name(&self) -> &str {
+        "annealing"
+    }
+
+    fn prepare(&mut self, inc: &mut Incumbents, inv_w: &[Vec<Vec<u64>>], ctx: &mut Context) {
+        // the initial temperature is a quarter of the mean cost difference between the current
+        // and a random position of a vertex
+        self.t0 = vec![1.0; inc.sccs.len()];
+        for i in inc.open() {
+            if ctx.cancel.is_cancelled() {
+                return;
+            }
+            let perm = &mut inc.current[i];
+            let mut sum = 0.0;
+            for _ in 0..SAMPLES {
+                let vpos = ctx.rng.gen_range(0..perm.len());
+                let v = perm.remove(vpos);
+                let cost = insert_cost_per_pos(&inc.sccs[i], &inv_w[i], perm, v);
+                let q = ctx.rng.gen_range(0..cost.len());
+                sum += (cost[q] as f64 - cost[vpos] as f64).abs();
+                perm.insert(vpos, v);
+            }
+            self.t0[i] = f64::max(1.0, sum / SAMPLES as f64 / 4.0);
+        }
+        ctx.phase("annealing");
+    }
+
+    /// n moves in every open SCC at the temperature reached after the elapsed part of the budget.
+    fn improve(&mut self, inc: &mut Incumbents, inv_w: &[Vec<Vec<u64>>], open: &[usize], ctx: &mut Context) -> bool {
+        let budget = ctx.time_limit.unwrap_or(DEFAULT_BUDGET).as_secs_f64();
+        let progress = f64::min(1.0, ctx.start.elapsed().as_secs_f64() / budget);
+        for i in open.iter().cloned() {
+            let n = inc.sccs[i].n;
+            let temperature = self.t0[i] * FINAL_RATIO.powf(progress);
+            let mut weights: Vec<f64> = vec![0.0; n];
+            for _ in 0..n {
+                if ctx.cancel.is_cancelled() {
+                    return false;
+                }
+                let perm = &mut inc.current[i];
+                let vpos = ctx.rng.gen_range(0..perm.len());
+                let v = perm.remove(vpos);
+                let cost = insert_cost_per_pos(&inc.sccs[i], &inv_w[i], perm, v);
+                let mincost = *cost.iter().min().unwrap();
+                let mut total = 0.0;
+                for q in 0..cost.len() {
+                    weights[q] = (-((cost[q] - mincost) as f64) / temperature).exp();
+                    total += weights[q];
+                }
+                let mut x = ctx.rng.gen::<f64>() * total;
+                let mut q = 0;
+                while q + 1 < cost.len() && x >= weights[q] {
+                    x -= weights[q];
+                    q += 1;
+                }
+                perm.insert(q, v);
+                inc.current_val[i] = inc.current_val[i] - cost[vpos] + cost[q];
+                if inc.accept(i) && !inc.is_open(i) {
+                    break;
+                }
+            }
+        }
+        true
+    }
+}
@@ -0,0 +1,39 @@
+// Writes the reduced instance as a linear ordering ILP and reads back the solutions of ILP solvers.
+// Usage: ilp lp <instance.gr> <model.lp>
+//        ilp mps <instance.gr> <model.mps>
+//        ilp import <instance.gr> <solution file>
+// import prints the resulting PACE ordering.
+use sisyphus::BipartiteGraph;
+use sisyphus::{ilp, sifting};
+use std::env;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::process;
+
+fn fail(msg: String) -> ! {
+    eprintln!("c {}", msg);
+    process::exit(1);
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() != 4 || !["lp", "mps", "import"].contains(&args[1].as_str()) {
+        fail(format!("Usage: {} lp|mps|import <instance.gr> <file>", args[0]));
+    }
+    let g = BipartiteGraph::from_path(&args[2]).unwrap_or_else(|e| fail(format!("Failed to read the graph: {}", e)));
+    let path = &args[3];
+    let (sccs, offset) = g.reduce_with_offset();
+    if args[1] == "import" {
+        let file = File::open(path).unwrap_or_else(|e| fail(format!("Failed to open {}: {}", path, e)));
+        let perm = ilp::read_solution(&sccs, BufReader::new(file)).unwrap_or_else(|e| fail(format!("Failed to read {}: {}", path, e)));
+        let ordering = g.input_ordering(&sifting::map_to_original_labels(&perm, &sccs));
+        eprintln!("c {} crossings", g.count_crossings(&ordering));
+        for u in &ordering { println!("{}", u); }
+    } else {
+        let file = File::create(path).unwrap_or_else(|e| fail(format!("Failed to create {}: {}", path, e)));
+        let mut out = BufWriter::new(file);
+        let res = if args[1] == "lp" { ilp::write_lp(&sccs, offset, &mut out) } else { ilp::write_mps(&sccs, offset, &mut out) };
+        res.unwrap_or_else(|e| fail(format!("Failed to write {}: {}", path, e)));
+        eprintln!("c the crossings are the objective value plus {}", ilp::objective_constant(&sccs, offset));
+    }
+}
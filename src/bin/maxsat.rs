@@ -0,0 +1,54 @@
+// Cross-checks sisyphus against MaxSAT solvers.
+// Usage: maxsat export <instance.gr> <prefix>
+//        maxsat import <instance.gr> <prefix>
+// export writes the WCNF instance of the i-th SCC of the reduced instance to <prefix>.<i>.wcnf
+// (SCCs of a single vertex are skipped). import reads the models from <prefix>.<i>.model and
+// prints the resulting PACE ordering.
+use sisyphus::BipartiteGraph;
+use sisyphus::{maxsat, sifting};
+use std::env;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::process;
+
+fn fail(msg: String) -> ! {
+    eprintln!("c {}", msg);
+    process::exit(1);
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() != 4 || (args[1] != "export" && args[1] != "import") {
+        fail(format!("Usage: {} export|import <instance.gr> <prefix>", args[0]));
+    }
+    let g = BipartiteGraph::from_path(&args[2]).unwrap_or_else(|e| fail(format!("Failed to read the graph: {}", e)));
+    let prefix = &args[3];
+    let (sccs, offset) = g.reduce_with_offset();
+    if args[1] == "export" {
+        let mut cnt = 0;
+        for (i, scc) in sccs.iter().enumerate() {
+            if scc.n == 1 {
+                continue;
+            }
+            let path = format!("{}.{}.wcnf", prefix, i);
+            let file = File::create(&path).unwrap_or_else(|e| fail(format!("Failed to create {}: {}", path, e)));
+            maxsat::write_wcnf(scc, &mut BufWriter::new(file)).unwrap_or_else(|e| fail(format!("Failed to write {}: {}", path, e)));
+            cnt += 1;
+        }
+        eprintln!("c wrote {} instances, the crossings are {} plus the sum of their optimal costs", cnt, offset);
+    } else {
+        let mut perm: Vec<Vec<usize>> = Vec::new();
+        for (i, scc) in sccs.iter().enumerate() {
+            if scc.n == 1 {
+                perm.push(vec![0]);
+                continue;
+            }
+            let path = format!("{}.{}.model", prefix, i);
+            let file = File::open(&path).unwrap_or_else(|e| fail(format!("Failed to open {}: {}", path, e)));
+            perm.push(maxsat::read_model(scc, BufReader::new(file)).unwrap_or_else(|e| fail(format!("Failed to read {}: {}", path, e))));
+        }
+        let ordering = g.input_ordering(&sifting::map_to_original_labels(&perm, &sccs));
+        eprintln!("c {} crossings", g.count_crossings(&ordering));
+        for u in &ordering { println!("{}", u); }
+    }
+}
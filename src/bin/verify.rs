@@ -0,0 +1,23 @@
+// Checks a solution against an instance and prints its number of crossings.
+// Usage: verify <instance.gr> <solution.sol>
+use sisyphus::bipartite_graph::{self, BipartiteGraph};
+use std::env;
+use std::process;
+
+fn fail(msg: String) -> ! {
+    eprintln!("c {}", msg);
+    process::exit(1);
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() != 3 {
+        fail(format!("Usage: {} <instance.gr> <solution.sol>", args[0]));
+    }
+    let g = BipartiteGraph::from_path(&args[1]).unwrap_or_else(|e| fail(format!("Failed to read the graph: {}", e)));
+    let ordering = bipartite_graph::read_ordering_from_path(&args[2]).unwrap_or_else(|e| fail(format!("Failed to read the solution: {}", e)));
+    if !g.is_valid_ordering(&ordering) {
+        fail(format!("The solution is not a permutation of {}, ..., {}", g.n0 + 1, g.n0 + g.num_free_vertices()));
+    }
+    println!("{}", g.count_crossings(&ordering));
+}
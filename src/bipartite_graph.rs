@@ -1,9 +1,114 @@
-use std::io::BufRead;
+use std::io::{self, BufRead, BufReader};
 use std::collections::HashMap;
 use std::error::Error;
-use crate::graph;
+use std::fmt;
+use std::fs::File;
+use std::path::Path;
+use crate::linear_ordering;
 use crate::scc::SCC;
-use std::cmp;
+
+/// Errors that can occur while reading a graph. Every variant except `Open` carries the (1-based)
+/// line number at which the problem was detected.
+#[derive(Debug)]
+pub enum ParseError {
+    Open { source: io::Error },
+    Io { line: usize, source: io::Error },
+    MissingHeader { line: usize },
+    InvalidHeader { line: usize },
+    WrongProblem { line: usize, found: String },
+    InvalidNumber { line: usize, token: String },
+    InvalidEdge { line: usize },
+    VertexOutOfRange { line: usize, vertex: usize },
+    EdgeInPartition { line: usize, a: usize, b: usize },
+    ReversedEdge { line: usize, a: usize, b: usize },
+    EdgeCountMismatch { line: usize, expected: usize, found: usize },
+    InvalidOrdering { line: usize },
+    InvalidModel { line: usize },
+    MissingEntries { line: usize, expected: usize, found: usize },
+    InvalidRanking { line: usize },
+}
+
+impl ParseError {
+    pub fn line(&self) -> Option<usize> {
+        match *self {
+            ParseError::Open { .. } => None,
+            ParseError::Io { line, .. }
+            | ParseError::MissingHeader { line }
+            | ParseError::InvalidHeader { line }
+            | ParseError::WrongProblem { line, .. }
+            | ParseError::InvalidNumber { line, .. }
+            | ParseError::InvalidEdge { line }
+            | ParseError::VertexOutOfRange { line, .. }
+            | ParseError::EdgeInPartition { line, .. }
+            | ParseError::ReversedEdge { line, .. }
+            | ParseError::EdgeCountMismatch { line, .. }
+            | ParseError::InvalidOrdering { line }
+            | ParseError::InvalidModel { line }
+            | ParseError::MissingEntries { line, .. }
+            | ParseError::InvalidRanking { line } => Some(line),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(line) = self.line() {
+            write!(f, "line {}: ", line)?;
+        }
+        match self {
+            ParseError::Open { source } => write!(f, "cannot open file: {}", source),
+            ParseError::Io { source, .. } => write!(f, "I/O error: {}", source),
+            ParseError::MissingHeader { .. } => write!(f, "found edge or end of input before p-line"),
+            ParseError::InvalidHeader { .. } => write!(f, "p-line must have the form 'p ocr n0 n1 m [cutwidth]'"),
+            ParseError::WrongProblem { found, .. } => write!(f, "expected problem 'ocr', found '{}'", found),
+            ParseError::InvalidNumber { token, .. } => write!(f, "'{}' is not a valid number", token),
+            ParseError::InvalidEdge { .. } => write!(f, "edge lines must contain two vertices"),
+            ParseError::VertexOutOfRange { vertex, .. } => write!(f, "vertex {} is out of range", vertex),
+            ParseError::EdgeInPartition { a, b, .. } => write!(f, "edge {} {} lies inside one partition", a, b),
+            ParseError::ReversedEdge { a, b, .. } => write!(f, "edge {} {} lists the free vertex first", a, b),
+            ParseError::EdgeCountMismatch { expected, found, .. } => write!(f, "p-line announces {} edges, found {}", expected, found),
+            ParseError::InvalidOrdering { .. } => write!(f, "the given linear ordering is not a permutation of all vertices"),
+            ParseError::InvalidModel { .. } => write!(f, "the model does not encode a linear ordering"),
+            ParseError::MissingEntries { expected, found, .. } => write!(f, "expected {} matrix entries, found {}", expected, found),
+            ParseError::InvalidRanking { .. } => write!(f, "the ranking lists an alternative twice or has unbalanced braces"),
+        }
+    }
+}
+
+impl Error for ParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ParseError::Open { source } | ParseError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+pub(crate) fn parse_number(token: &str, line: usize) -> Result<usize, ParseError> {
+    token.parse::<usize>().map_err(|_| ParseError::InvalidNumber { line, token: token.to_string() })
+}
+
+/// Reads an ordering in the PACE 2024 solution format (one vertex per line, comments allowed).
+pub fn read_ordering<R: BufRead>(reader: R) -> Result<Vec<usize>, ParseError> {
+    let mut ordering: Vec<usize> = Vec::new();
+    let mut lineno: usize = 0;
+    for line in reader.lines() {
+        lineno += 1;
+        let line = line.map_err(|e| ParseError::Io { line: lineno, source: e })?;
+        let ll: Vec<&str> = line.split_whitespace().collect();
+        if ll.is_empty() || ll[0] == "c" {
+            continue;
+        }
+        ordering.push(parse_number(ll[0], lineno)?);
+    }
+    Ok(ordering)
+}
+
+/// Reads an ordering in the PACE 2024 solution format from the file at `path`.
+pub fn read_ordering_from_path<P: AsRef<Path>>(path: P) -> Result<Vec<usize>, ParseError> {
+    let file = File::open(path).map_err(|e| ParseError::Open { source: e })?;
+    read_ordering(BufReader::new(file))
+}
 
 pub struct BipartiteGraph {
     pub n0: usize,
@@ -11,37 +116,98 @@ pub struct BipartiteGraph {
     pub adjs: Vec<Vec<usize>>, // neighbors of vertices 1, ..., n1 in second partition
     pub ids: Vec<Vec<usize>>, // original ids of vertices 1, ..., n1
     pub isolated: Vec<usize>, // ids of additional isolated vertices
+    pub cutwidth: Option<usize>, // cutwidth announced by the parameterized track
+    pub linear_ordering: Option<Vec<usize>>, // vertex ordering (input ids) of the parameterized track
 }
 
 impl BipartiteGraph {
     pub fn new(n0: usize, n1: usize, adjs: Vec<Vec<usize>>, ids: Vec<Vec<usize>>, isolated: Vec<usize>) -> BipartiteGraph {
-        BipartiteGraph { n0, n1, adjs, ids, isolated }
+        BipartiteGraph { n0, n1, adjs, ids, isolated, cutwidth: None, linear_ordering: None }
+    }
+
+    /// Reads a graph in the PACE 2024 format from standard input.
+    pub fn new_from_stdin() -> Result<BipartiteGraph, ParseError> {
+        BipartiteGraph::from_reader(std::io::stdin().lock())
+    }
+
+    /// Reads a graph in the PACE 2024 format from the file at `path`.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<BipartiteGraph, ParseError> {
+        let file = File::open(path).map_err(|e| ParseError::Open { source: e })?;
+        BipartiteGraph::from_reader(BufReader::new(file))
     }
 
-    pub fn new_from_stdin() -> Result<BipartiteGraph, Box<dyn Error>> {
+    /// Reads a graph in the PACE 2024 format from any buffered reader. Instances of the
+    /// parameterized track (a p-line with cutwidth followed by a vertex ordering) are supported;
+    /// the ordering is checked to be a permutation of all vertices and kept in `linear_ordering`.
+    #[allow(clippy::needless_range_loop)]
+    pub fn from_reader<R: BufRead>(reader: R) -> Result<BipartiteGraph, ParseError> {
         let mut ingraph: Option<Vec<Vec<usize>>> = None;
         let mut n0: usize = 0;
-        for line in std::io::stdin().lock().lines() {
-            let line = line?;
-            let ll: Vec<&str> = line.split(' ').collect();
+        let mut m: usize = 0;
+        let mut cutwidth: Option<usize> = None;
+        let mut linear_ordering: Vec<usize> = Vec::new();
+        let mut edges: usize = 0;
+        let mut lineno: usize = 0;
+        for line in reader.lines() {
+            lineno += 1;
+            let line = line.map_err(|e| ParseError::Io { line: lineno, source: e })?;
+            let ll: Vec<&str> = line.split_whitespace().collect();
+            if ll.is_empty() {
+                continue;
+            }
             match ll[0] {
                 "c" => {} // skip comments 
                 "p" => { // parse header 
-                    let a = ll[2].parse::<usize>()?;
-                    let b = ll[3].parse::<usize>()?;
+                    if ll.len() < 5 {
+                        return Err(ParseError::InvalidHeader { line: lineno });
+                    }
+                    if ll[1] != "ocr" {
+                        return Err(ParseError::WrongProblem { line: lineno, found: ll[1].to_string() });
+                    }
+                    let a = parse_number(ll[2], lineno)?;
+                    let b = parse_number(ll[3], lineno)?;
+                    m = parse_number(ll[4], lineno)?;
+                    if ll.len() > 5 {
+                        cutwidth = Some(parse_number(ll[5], lineno)?);
+                    }
                     n0 = a;
                     ingraph = Some(vec![Vec::new(); b]);
                 },
                 _ => { // parse                    
                     match ingraph {
-                        None => return Err(From::from("c Found edge before p-line. Abort!")),
+                        None => return Err(ParseError::MissingHeader { line: lineno }),
                         Some(ref mut ingraph) => {
-                            if ll.len() < 2 {
+                            if cutwidth.is_some() && linear_ordering.len() < n0 + ingraph.len() {
+                                // the ordering block precedes the edges in the parameterized track
+                                if ll.len() != 1 {
+                                    return Err(ParseError::InvalidOrdering { line: lineno });
+                                }
+                                let x = parse_number(ll[0], lineno)?;
+                                if x == 0 || x > n0 + ingraph.len() {
+                                    return Err(ParseError::VertexOutOfRange { line: lineno, vertex: x });
+                                }
+                                linear_ordering.push(x);
                                 continue;
                             }
-                            let a = ll[0].parse::<usize>()?;
-                            let b = ll[1].parse::<usize>()?;
+                            if ll.len() < 2 {
+                                return Err(ParseError::InvalidEdge { line: lineno });
+                            }
+                            let a = parse_number(ll[0], lineno)?;
+                            let b = parse_number(ll[1], lineno)?;
+                            let n = n0 + ingraph.len();
+                            for x in [a, b] {
+                                if x == 0 || x > n {
+                                    return Err(ParseError::VertexOutOfRange { line: lineno, vertex: x });
+                                }
+                            }
+                            if (a <= n0) == (b <= n0) {
+                                return Err(ParseError::EdgeInPartition { line: lineno, a, b });
+                            }
+                            if a > n0 {
+                                return Err(ParseError::ReversedEdge { line: lineno, a, b });
+                            }
                             ingraph[b-n0-1].push(a-1);
+                            edges += 1;
                         }
                     }
                 }
@@ -49,20 +215,49 @@ impl BipartiteGraph {
         }
         match ingraph {
             Some(mut ingraph) => {
+                if cutwidth.is_some() {
+                    let mut seen = vec![false; n0 + ingraph.len()];
+                    for x in linear_ordering.iter().cloned() {
+                        if seen[x-1] {
+                            return Err(ParseError::InvalidOrdering { line: lineno });
+                        }
+                        seen[x-1] = true;
+                    }
+                    if linear_ordering.len() != seen.len() {
+                        return Err(ParseError::InvalidOrdering { line: lineno });
+                    }
+                }
+                if edges != m {
+                    return Err(ParseError::EdgeCountMismatch { line: lineno, expected: m, found: edges });
+                }
+                // twin classes are numbered by their smallest member to keep runs reproducible
                 let mut isolated: Vec<usize> = Vec::new();
-                let mut adjtotwins: HashMap<Vec<usize>, Vec<usize>> = HashMap::new();
+                let mut adjtoclass: HashMap<Vec<usize>, usize> = HashMap::new();
+                let mut adjs: Vec<Vec<usize>> = Vec::new();
+                let mut ids: Vec<Vec<usize>> = Vec::new();
                 for i in 0..ingraph.len() {
                     if ingraph[i].is_empty() {
                         isolated.push(i);
                     } else {
                         ingraph[i].sort();
-                        adjtotwins.entry(ingraph[i].clone()).and_modify(|twins| twins.push(i)).or_insert(vec![i]);
+                        match adjtoclass.get(&ingraph[i]) {
+                            Some(&class) => ids[class].push(i),
+                            None => {
+                                adjtoclass.insert(ingraph[i].clone(), adjs.len());
+                                adjs.push(ingraph[i].clone());
+                                ids.push(vec![i]);
+                            }
+                        }
                     }
                 }
-                let (adjs, ids): (Vec<Vec<usize>>, Vec<Vec<usize>>) = adjtotwins.into_iter().unzip();
-                Ok(BipartiteGraph::new(n0, ids.len(), adjs, ids, isolated))
+                let mut g = BipartiteGraph::new(n0, ids.len(), adjs, ids, isolated);
+                g.cutwidth = cutwidth;
+                if cutwidth.is_some() {
+                    g.linear_ordering = Some(linear_ordering);
+                }
+                Ok(g)
             },
-            None => Err(From::from("c Failed to parse a graph! Maybe the input was empty?"))
+            None => Err(ParseError::MissingHeader { line: lineno })
         }
     }
 
@@ -85,6 +280,115 @@ impl BipartiteGraph {
         cn as u64
     }
 
+    /// Number of vertices in the free partition of the input, including twins and isolated vertices.
+    pub fn num_free_vertices(&self) -> usize {
+        self.isolated.len() + self.ids.iter().map(|twins| twins.len()).sum::<usize>()
+    }
+
+    // maps each free vertex (0-based input id) to its twin class, isolated vertices to None
+    fn twin_classes(&self) -> Vec<Option<usize>> {
+        let mut class: Vec<Option<usize>> = vec![None; self.num_free_vertices()];
+        for v in 0..self.n1 {
+            for twin in self.ids[v].iter().cloned() {
+                class[twin] = Some(v);
+            }
+        }
+        class
+    }
+
+    /// Checks whether `ordering` is a permutation of the free vertices n0+1, ..., n0+n1 (input ids).
+    pub fn is_valid_ordering(&self, ordering: &[usize]) -> bool {
+        let n = self.num_free_vertices();
+        let mut seen = vec![false; n];
+        for x in ordering.iter().cloned() {
+            if x <= self.n0 || x > self.n0 + n || seen[x-self.n0-1] {
+                return false;
+            }
+            seen[x-self.n0-1] = true;
+        }
+        ordering.len() == n
+    }
+
+    /// Translates an ordering of the twin classes 0, ..., n1-1 into a PACE ordering of input ids
+    /// n0+1, ..., n0+n1. Isolated vertices come first, twins are placed next to each other.
+    pub fn input_ordering(&self, reduced: &[usize]) -> Vec<usize> {
+        let mut ordering: Vec<usize> = Vec::new();
+        for u in self.isolated.iter().cloned() {
+            ordering.push(u);
+        }
+        for v in reduced.iter().cloned() {
+            for twin in self.ids[v].iter().cloned() {
+                ordering.push(twin);
+            }
+        }
+        for el in &mut ordering {
+            *el += self.n0 + 1;
+        }
+        ordering
+    }
+
+    /// Translates an ordering of input ids n0+1, ..., n0+n1 into an ordering of the twin classes
+    /// 0, ..., n1-1. Each class is placed at the first occurrence of one of its twins, classes that
+    /// do not occur are appended in increasing order and unknown ids are ignored.
+    #[allow(clippy::needless_range_loop)]
+    pub fn reduced_ordering(&self, ordering: &[usize]) -> Vec<usize> {
+        let n = self.num_free_vertices();
+        let class = self.twin_classes();
+        let mut placed = vec![false; self.n1];
+        let mut reduced: Vec<usize> = Vec::with_capacity(self.n1);
+        for x in ordering.iter().cloned() {
+            if x <= self.n0 || x > self.n0 + n {
+                continue;
+            }
+            if let Some(v) = class[x-self.n0-1] {
+                if !placed[v] {
+                    placed[v] = true;
+                    reduced.push(v);
+                }
+            }
+        }
+        for v in 0..self.n1 {
+            if !placed[v] {
+                reduced.push(v);
+            }
+        }
+        reduced
+    }
+
+    /// Counts the crossings of the input graph if the free vertices are ordered as in `ordering`,
+    /// which contains input ids n0+1, ..., n0+n1 (see `is_valid_ordering`). Uses the accumulator
+    /// tree of Barth, Jünger and Mutzel and runs in O(m log n0).
+    pub fn count_crossings(&self, ordering: &[usize]) -> u64 {
+        let class = self.twin_classes();
+        let mut firstindex = 1;
+        while firstindex < self.n0 {
+            firstindex *= 2;
+        }
+        let mut tree: Vec<u64> = vec![0; 2*firstindex - 1];
+        firstindex -= 1;
+        let mut crossings: u64 = 0;
+        for x in ordering.iter().cloned() {
+            let v = match class[x-self.n0-1] {
+                Some(v) => v,
+                None => continue,
+            };
+            // adjacency lists are sorted, so edges of a vertex never cross each other
+            for a in self.adjs[v].iter().cloned() {
+                let mut index = a + firstindex;
+                tree[index] += 1;
+                while index > 0 {
+                    if index % 2 == 1 {
+                        crossings += tree[index+1];
+                    }
+                    index = (index - 1) / 2;
+                    tree[index] += 1;
+                }
+            }
+        }
+        crossings
+    }
+
+    #[allow(clippy::needless_range_loop)]
     pub fn crossing_matrix(&self) -> Vec<Vec<u64>> {
         let mut cm = vec![vec![0; self.n1]; self.n1];
         for u in 0..self.n1 {
@@ -99,37 +403,95 @@ impl BipartiteGraph {
     }
 
     pub fn reduce(&self) -> Vec<SCC> {
-        let mut cm = self.crossing_matrix();
-        for u in 0..cm.len() {
-            for v in u+1..cm.len() {
-                let mn: u64 = cmp::min(cm[u][v], cm[v][u]);
-                cm[u][v] -= mn;
-                cm[v][u] -= mn;
-            }
-        } 
-        let mut h: Vec<Vec<usize>> = vec![Vec::new(); cm.len()];
-        for u in 0..cm.len() {
-            for v in 0..cm.len() {
-                if cm[v][u] != 0 {
-                    h[u].push(v); 
-                }
-            }
-        } 
-        let sccs = graph::compute_sccs(&h);
-        let mut result: Vec<SCC> = Vec::new(); 
-        for scc in sccs.iter().cloned() {
-            let mut w: Vec<Vec<u64>> = vec![vec![0; scc.len()]; scc.len()];
-            let mut g: Vec<Vec<usize>> = vec![Vec::new(); scc.len()];
-            for i in 0..scc.len() {
-                for j in 0..scc.len() {
-                    w[i][j] = cm[scc[j]][scc[i]];
-                    if w[i][j] != 0 {
-                        g[i].push(j);
-                    }
-                }
-            }
-            result.push(SCC::new(scc, w, g));
+        self.reduce_with_offset().0
+    }
+
+    /// Like `reduce`, but also returns the number of crossings that every ordering incurs: the sum
+    /// of min(c(u,v), c(v,u)) over all pairs, which is subtracted from the weights, plus the
+    /// crossings among twins.
+    pub fn reduce_with_offset(&self) -> (Vec<SCC>, u64) {
+        let cm = self.crossing_matrix();
+        let mut offset: u64 = 0;
+        for u in 0..self.n1 {
+            let k = self.ids[u].len() as u64;
+            offset += self.pair_crossing_number(u, u) * (k * (k - 1) / 2);
         }
-        result
+        let (sccs, mins) = linear_ordering::reduce_matrix(cm);
+        (sccs, offset + mins)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kemeny::Profile;
+    use crate::lop::LopInstance;
+    use crate::maxsat;
+
+    type Parser = fn(&[u8]) -> Result<(), ParseError>;
+    type Matcher = fn(&ParseError) -> bool;
+
+    fn graph(input: &[u8]) -> Result<(), ParseError> {
+        BipartiteGraph::from_reader(input).map(|_| ())
+    }
+
+    fn ordering(input: &[u8]) -> Result<(), ParseError> {
+        read_ordering(input).map(|_| ())
+    }
+
+    fn lop(input: &[u8]) -> Result<(), ParseError> {
+        LopInstance::from_reader(input).map(|_| ())
+    }
+
+    fn kemeny(input: &[u8]) -> Result<(), ParseError> {
+        Profile::from_reader(input).map(|_| ())
+    }
+
+    // model of an scc with three vertices, i.e., three variables
+    fn model(input: &[u8]) -> Result<(), ParseError> {
+        let scc = SCC::new(vec![0, 1, 2], vec![vec![0; 3]; 3], vec![Vec::new(); 3]);
+        maxsat::read_model(&scc, input).map(|_| ())
+    }
+
+    #[test]
+    fn errors_carry_variant_and_line() {
+        let cases: Vec<(Parser, &[u8], Matcher, usize)> = vec![
+            (graph, b"p ocr 1 1 0\n\xff\n", |e| matches!(e, ParseError::Io { .. }), 2),
+            (graph, b"c comment\n1 2\n", |e| matches!(e, ParseError::MissingHeader { .. }), 2),
+            (graph, b"c comment\n", |e| matches!(e, ParseError::MissingHeader { .. }), 1),
+            (graph, b"p ocr 1 1\n", |e| matches!(e, ParseError::InvalidHeader { .. }), 1),
+            (graph, b"c comment\np tsp 1 1 0\n", |e| matches!(e, ParseError::WrongProblem { found, .. } if found == "tsp"), 2),
+            (graph, b"p ocr 1 x 0\n", |e| matches!(e, ParseError::InvalidNumber { token, .. } if token == "x"), 1),
+            (graph, b"p ocr 1 1 1\n1\n", |e| matches!(e, ParseError::InvalidEdge { .. }), 2),
+            (graph, b"p ocr 1 1 1\n1 3\n", |e| matches!(e, ParseError::VertexOutOfRange { vertex: 3, .. }), 2),
+            (graph, b"p ocr 2 1 1\n1 2\n", |e| matches!(e, ParseError::EdgeInPartition { a: 1, b: 2, .. }), 2),
+            (graph, b"p ocr 2 2 1\n3 1\n", |e| matches!(e, ParseError::ReversedEdge { a: 3, b: 1, .. }), 2),
+            (graph, b"p ocr 1 1 2\n1 2\n\n", |e| matches!(e, ParseError::EdgeCountMismatch { expected: 2, found: 1, .. }), 3),
+            (graph, b"p ocr 1 1 1 1\n1 2\n", |e| matches!(e, ParseError::InvalidOrdering { .. }), 2),
+            (graph, b"p ocr 1 1 1 1\n1\n1\n1 2\n", |e| matches!(e, ParseError::InvalidOrdering { .. }), 4),
+            (graph, b"p ocr 1 1 1 1\n1\n3\n", |e| matches!(e, ParseError::VertexOutOfRange { vertex: 3, .. }), 3),
+            (ordering, b"c comment\n2\nx\n", |e| matches!(e, ParseError::InvalidNumber { .. }), 3),
+            (lop, b"instance\n2\n1 2\n3\n", |e| matches!(e, ParseError::MissingEntries { expected: 4, found: 3, .. }), 4),
+            (lop, b"instance\n", |e| matches!(e, ParseError::MissingHeader { .. }), 1),
+            (kemeny, b"# NUMBER ALTERNATIVES: 2\n1: 1,1\n", |e| matches!(e, ParseError::InvalidRanking { .. }), 2),
+            (kemeny, b"# NUMBER ALTERNATIVES: 2\n1: 1,2\n1: {1,2\n", |e| matches!(e, ParseError::InvalidRanking { .. }), 3),
+            (kemeny, b"# NUMBER ALTERNATIVES: 2\n1: 1,3\n", |e| matches!(e, ParseError::VertexOutOfRange { vertex: 3, .. }), 2),
+            (kemeny, b"# NUMBER ALTERNATIVES: 2\n1 2\n", |e| matches!(e, ParseError::InvalidOrdering { .. }), 2),
+            (model, b"o 0\nv 1 2 4\n", |e| matches!(e, ParseError::InvalidModel { .. }), 2),
+            (model, b"v 1 -2 3\ns OPTIMUM FOUND\n", |e| matches!(e, ParseError::InvalidModel { .. }), 2),
+        ];
+        for (k, (parse, input, expected, line)) in cases.into_iter().enumerate() {
+            let e = parse(input).expect_err(&format!("case {}", k));
+            assert!(expected(&e), "case {}: {:?}", k, e);
+            assert_eq!(e.line(), Some(line), "case {}: {}", k, e);
+        }
+    }
+
+    #[test]
+    fn open_errors_have_no_line() {
+        let e = BipartiteGraph::from_path("/nonexistent/graph.gr").err().unwrap();
+        assert!(matches!(e, ParseError::Open { .. }));
+        assert_eq!(e.line(), None);
+        assert!(e.to_string().starts_with("cannot open file"));
     }
 }
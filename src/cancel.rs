@@ -0,0 +1,56 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Weak};
+use std::thread;
+use std::time::Duration;
+
+/// Cooperative cancellation of a solve. Clones share the same state, so a token can be handed to a
+/// solver and cancelled from another thread (e.g., a signal handler).
+#[derive(Clone, Default)]
+pub struct CancelToken {
+    flag: Arc<AtomicBool>,
+    parents: Vec<Arc<AtomicBool>>,
+}
+
+impl CancelToken {
+    pub fn new() -> CancelToken {
+        CancelToken::default()
+    }
+
+    /// Returns a token that is cancelled together with this one, but can also be cancelled on its
+    /// own without affecting this token.
+    pub fn child(&self) -> CancelToken {
+        let mut parents = self.parents.clone();
+        parents.push(self.flag.clone());
+        CancelToken { flag: Arc::new(AtomicBool::new(false)), parents }
+    }
+
+    pub fn cancel(&self) {
+        self.flag.store(true, Ordering::Relaxed);
+    }
+
+    /// Clears the cancellation of this token (not of its parents), so it can be used again.
+    pub fn reset(&self) {
+        self.flag.store(false, Ordering::Relaxed);
+    }
+
+    #[inline(always)]
+    pub fn is_cancelled(&self) -> bool {
+        self.flag.load(Ordering::Relaxed) || self.parents.iter().any(|p| p.load(Ordering::Relaxed))
+    }
+
+    /// Cancels this token once `limit` has elapsed, unless all its clones have been dropped by then.
+    /// A zero limit cancels it at once.
+    pub fn cancel_after(&self, limit: Duration) {
+        if limit.is_zero() {
+            self.cancel();
+            return;
+        }
+        let flag: Weak<AtomicBool> = Arc::downgrade(&self.flag);
+        thread::spawn(move || {
+            thread::sleep(limit);
+            if let Some(flag) = flag.upgrade() {
+                flag.store(true, Ordering::Relaxed);
+            }
+        });
+    }
+}
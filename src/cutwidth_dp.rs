@@ -0,0 +1,356 @@
+// Exact solver for instances of the parameterized track.
+//
+// For two free vertices u and v with max N(u) <= min N(v) placing u before v causes no crossings,
+// and there is an optimal ordering respecting all of these pairs (after merging twins they form an
+// interval order on the neighbourhood spans). The dynamic program below runs over the ideals of
+// this order, sweeping the fixed layer from left to right: an ideal is described by the prefix of
+// vertices whose span ended before the sweep position plus a subset of the vertices whose span
+// contains it, so there are at most n1 * 2^a states if at most a spans are open at any point.
+//
+// If the vertex ordering given with the instance lists the fixed vertices in their order and has
+// cutwidth k, then a <= 2k: a free vertex u whose span contains y is either placed after y and
+// its edge to min N(u) crosses the cut behind y, or it is placed before y and its edge to
+// max N(u) crosses the cut in front of y. The state space is then bounded by n1 * 4^k and the
+// dynamic program is run without a limit. Without such an ordering the overlap is unbounded (the
+// problem stays NP-hard for disjoint stars, whose cutwidth is 2), so `MAX_STATES` applies.
+use crate::BipartiteGraph;
+use crate::cancel::CancelToken;
+use crate::strategy::{self, Context, Strategy};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Maximum number of states before the dynamic program gives up, unless the given ordering
+/// bounds the state space.
+const MAX_STATES: usize = 1 << 22;
+
+struct State {
+    cost: u64,
+    parent: usize,
+    v: usize,
+}
+
+#[inline(always)]
+fn weight(g: &BipartiteGraph, u: usize, v: usize) -> u64 {
+    g.pair_crossing_number(u, v) * g.ids[u].len() as u64 * g.ids[v].len() as u64
+}
+
+/// Whether the graph comes with a linear ordering that lists the fixed vertices 1, ..., n0 in
+/// increasing order, which bounds the state space by its cutwidth.
+pub fn bounded_by_ordering(g: &BipartiteGraph) -> bool {
+    match g.linear_ordering {
+        Some(ref ordering) => {
+            let fixed: Vec<usize> = ordering.iter().cloned().filter(|&x| x <= g.n0).collect();
+            fixed.windows(2).all(|w| w[0] < w[1])
+        },
+        None => false,
+    }
+}
+
+/// Returns an optimal ordering of the (twin-reduced) free vertices 0..g.n1, or None if the
+/// computation was aborted or, unless `bounded_by_ordering` holds, the state space exceeds
+/// `MAX_STATES`.
+pub fn solve(g: &BipartiteGraph, cancel: &CancelToken) -> Option<Vec<usize>> {
+    let limit = if bounded_by_ordering(g) { usize::MAX } else { MAX_STATES };
+    solve_with_limit(g, limit, cancel)
+}
+
+fn solve_with_limit(g: &BipartiteGraph, limit: usize, cancel: &CancelToken) -> Option<Vec<usize>> {
+    let n = g.n1;
+    if n == 0 {
+        return Some(Vec::new());
+    }
+    // span of the neighbourhood of each free vertex (adjacency lists are sorted)
+    let l: Vec<usize> = g.adjs.iter().map(|a| a[0]).collect();
+    let r: Vec<usize> = g.adjs.iter().map(|a| a[a.len()-1]).collect();
+    let mut by_r: Vec<usize> = (0..n).collect();
+    by_r.sort_by_key(|&v| (r[v], l[v]));
+    let mut rank = vec![0; n];
+    for q in 0..n {
+        rank[by_r[q]] = q;
+    }
+    let mut by_l: Vec<usize> = (0..n).collect();
+    by_l.sort_by_key(|&v| l[v]);
+
+    // active[p]: ranks q >= p whose span starts before the end of the span of by_r[p] (always
+    // including p itself); lstart[p]: first index of by_l whose span starts at or after that point
+    let mut active: Vec<Vec<usize>> = Vec::with_capacity(n);
+    let mut lstart: Vec<usize> = Vec::with_capacity(n);
+    let mut open: BTreeSet<usize> = BTreeSet::new();
+    let mut idx = 0;
+    for p in 0..n {
+        let y = r[by_r[p]];
+        while idx < n && l[by_l[idx]] < y {
+            open.insert(rank[by_l[idx]]);
+            idx += 1;
+        }
+        while let Some(&q) = open.first() {
+            if q >= p {
+                break;
+            }
+            open.remove(&q);
+        }
+        let mut act: Vec<usize> = open.iter().cloned().collect();
+        if act.first() != Some(&p) {
+            act.insert(0, p);
+        }
+        active.push(act);
+        lstart.push(idx);
+    }
+
+    // a state (p, extras) is the ideal containing all ranks < p and the ranks in extras
+    let mut states: Vec<State> = vec![State { cost: 0, parent: usize::MAX, v: usize::MAX }];
+    let mut layer: BTreeMap<(usize, Vec<usize>), usize> = BTreeMap::new();
+    layer.insert((0, Vec::new()), 0);
+    for _ in 0..n {
+        let mut next: BTreeMap<(usize, Vec<usize>), usize> = BTreeMap::new();
+        for ((p, extras), &si) in layer.iter() {
+            if cancel.is_cancelled() {
+                return None;
+            }
+            let p = *p;
+            let mut q = p + 1;
+            while q < n && extras.binary_search(&q).is_ok() {
+                q += 1;
+            }
+            let y2 = if q < n { r[by_r[q]] } else { usize::MAX };
+            for &c in active[p].iter() {
+                if extras.binary_search(&c).is_ok() {
+                    continue;
+                }
+                if c == p && l[by_r[p]] >= y2 {
+                    continue;
+                }
+                let v = by_r[c];
+                let mut cost = 0;
+                for &u in active[p].iter() {
+                    if u == c || extras.binary_search(&u).is_ok() {
+                        continue;
+                    }
+                    cost += weight(g, v, by_r[u]);
+                }
+                let mut j = lstart[p];
+                while j < n && l[by_l[j]] < r[v] {
+                    let u = by_l[j];
+                    if rank[u] > p && rank[u] != c {
+                        cost += weight(g, v, u);
+                    }
+                    j += 1;
+                }
+
+                let mut nextp = p;
+                let mut nextextras = extras.clone();
+                if c == p {
+                    nextp += 1;
+                } else {
+                    let pos = nextextras.binary_search(&c).unwrap_err();
+                    nextextras.insert(pos, c);
+                }
+                while !nextextras.is_empty() && nextextras[0] == nextp {
+                    nextextras.remove(0);
+                    nextp += 1;
+                }
+                let total = states[si].cost + cost;
+                match next.get(&(nextp, nextextras.clone())) {
+                    Some(&ti) => {
+                        if total < states[ti].cost {
+                            states[ti] = State { cost: total, parent: si, v };
+                        }
+                    },
+                    None => {
+                        states.push(State { cost: total, parent: si, v });
+                        next.insert((nextp, nextextras), states.len() - 1);
+                    }
+                }
+                if states.len() > limit {
+                    return None;
+                }
+            }
+        }
+        layer = next;
+    }
+
+    let mut si = *layer.get(&(n, Vec::new()))?;
+    let mut ordering = Vec::with_capacity(n);
+    while states[si].parent != usize::MAX {
+        ordering.push(states[si].v);
+        si = states[si].parent;
+    }
+    ordering.reverse();
+    Some(ordering)
+}
+
+/// Strategy wrapper around `solve`. If the state space is too large (only possible without a
+/// bounding ordering), it falls back to the sifting strategy for the size of the instance and
+/// reports this as a new phase.
+pub struct CutwidthDp {
+    result: Option<Vec<usize>>,
+    fallback: Option<Box<dyn Strategy>>,
+}
+
+impl CutwidthDp {
+    pub fn new() -> CutwidthDp {
+        CutwidthDp { result: None, fallback: None }
+    }
+}
+
+impl Default for CutwidthDp {
+    fn default() -> CutwidthDp {
+        CutwidthDp::new()
+    }
+}
+
+impl Strategy for CutwidthDp {
+    fn name(&self) -> &str {
+        match self.fallback {
+            Some(ref fallback) => fallback.name(),
+            None => "cutwidth-dp",
+        }
+    }
+
+    fn needs_graph(&self) -> bool {
+        true
+    }
+
+    fn prepare(&mut self, _ctx: &mut Context) {}
+
+    fn improve(&mut self, ctx: &mut Context) -> bool {
+        if let Some(ref mut fallback) = self.fallback {
+            return fallback.improve(ctx);
+        }
+        let g = ctx.graph().expect("needs a graph");
+        self.result = solve(g, &ctx.cancel);
+        if self.result.is_some() {
+            return false;
+        }
+        let mut fallback = strategy::for_size(g.n1);
+        ctx.phase(&format!("state space too large, falling back to {}", fallback.name()));
+        fallback.prepare(ctx);
+        let more = fallback.improve(ctx);
+        self.fallback = Some(fallback);
+        more
+    }
+
+    fn best(&self) -> Vec<usize> {
+        match self.fallback {
+            Some(ref fallback) => fallback.best(),
+            None => self.result.clone().unwrap_or_default(),
+        }
+    }
+
+    fn lower_bound(&self) -> u64 {
+        self.fallback.as_ref().map_or(0, |fallback| fallback.lower_bound())
+    }
+
+    fn proved_optimal(&self) -> bool {
+        self.result.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand::rngs::StdRng;
+
+    fn cost(cm: &[Vec<u64>], ordering: &[usize]) -> u64 {
+        let mut total = 0;
+        for i in 0..ordering.len() {
+            for j in (i+1)..ordering.len() {
+                total += cm[ordering[i]][ordering[j]];
+            }
+        }
+        total
+    }
+
+    // minimum over all orderings of the free vertices 0..n that extend prefix
+    fn brute_force(cm: &[Vec<u64>], prefix: &mut Vec<usize>, n: usize) -> u64 {
+        if prefix.len() == n {
+            return cost(cm, prefix);
+        }
+        let mut best = u64::MAX;
+        for v in 0..n {
+            if !prefix.contains(&v) {
+                prefix.push(v);
+                best = best.min(brute_force(cm, prefix, n));
+                prefix.pop();
+            }
+        }
+        best
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..200 {
+            let n0 = rng.gen_range(1..=6);
+            let n1 = rng.gen_range(1..=7);
+            let mut edges: Vec<(usize, usize)> = Vec::new();
+            for a in 1..=n0 {
+                for b in 1..=n1 {
+                    if rng.gen_bool(0.4) {
+                        edges.push((a, n0 + b));
+                    }
+                }
+            }
+            let mut input = format!("p ocr {} {} {}\n", n0, n1, edges.len());
+            for (a, b) in edges.iter() {
+                input.push_str(&format!("{} {}\n", a, b));
+            }
+            let g = BipartiteGraph::from_reader(input.as_bytes()).unwrap();
+            let cm = g.crossing_matrix();
+            let ordering = solve(&g, &CancelToken::new()).unwrap();
+            let mut sorted = ordering.clone();
+            sorted.sort();
+            assert_eq!(sorted, (0..g.n1).collect::<Vec<usize>>());
+            assert_eq!(cost(&cm, &ordering), brute_force(&cm, &mut Vec::new(), g.n1), "{}", input);
+        }
+    }
+
+    // maximum number of edges between a prefix of ordering and the rest
+    fn cutwidth(ordering: &[usize], edges: &[(usize, usize)]) -> usize {
+        let mut pos = vec![0; ordering.len() + 1];
+        for (i, x) in ordering.iter().enumerate() {
+            pos[*x] = i;
+        }
+        (0..ordering.len()).map(|i| {
+            edges.iter().filter(|(a, b)| pos[*a].min(pos[*b]) <= i && pos[*a].max(pos[*b]) > i).count()
+        }).max().unwrap_or(0)
+    }
+
+    #[test]
+    fn states_are_bounded_by_the_cutwidth() {
+        let mut rng = StdRng::seed_from_u64(2);
+        for _ in 0..200 {
+            let n0 = rng.gen_range(1..=8);
+            let n1 = rng.gen_range(1..=8);
+            let mut edges: Vec<(usize, usize)> = Vec::new();
+            for a in 1..=n0 {
+                for b in 1..=n1 {
+                    if rng.gen_bool(0.3) {
+                        edges.push((a, n0 + b));
+                    }
+                }
+            }
+            // fixed vertices in their order, free vertices inserted at random positions
+            let mut ordering: Vec<usize> = (1..=n0).collect();
+            for b in 1..=n1 {
+                let i = rng.gen_range(0..=ordering.len());
+                ordering.insert(i, n0 + b);
+            }
+            let k = cutwidth(&ordering, &edges);
+            let mut input = format!("p ocr {} {} {} {}\n", n0, n1, edges.len(), k);
+            for x in ordering.iter() {
+                input.push_str(&format!("{}\n", x));
+            }
+            for (a, b) in edges.iter() {
+                input.push_str(&format!("{} {}\n", a, b));
+            }
+            let g = BipartiteGraph::from_reader(input.as_bytes()).unwrap();
+            assert_eq!(g.linear_ordering.as_deref(), Some(&ordering[..]));
+            assert!(bounded_by_ordering(&g));
+            let cm = g.crossing_matrix();
+            let limit = g.n1.max(1) << (2*k);
+            let reduced = solve_with_limit(&g, limit, &CancelToken::new()).expect(&input);
+            assert_eq!(cost(&cm, &reduced), brute_force(&cm, &mut Vec::new(), g.n1), "{}", input);
+        }
+    }
+}
@@ -0,0 +1,241 @@
+// Exact solver for the exact track: every SCC of the reduced instance is ordered optimally by a
+// branch and bound over prefixes of the ordering, seeded with the result of a few sifting runs.
+// The remaining vertices of a prefix cost at least the weight of the packed 3-cycles (see
+// `lower_bound::packed_cycles`) that lie entirely among them.
+use crate::scc::SCC;
+use crate::cancel::CancelToken;
+use crate::lower_bound;
+use crate::sifting;
+use crate::strategy::{Context, Strategy};
+use std::cmp;
+
+/// Number of sifting runs used to compute the initial incumbents.
+const SEED_RUNS: usize = 16;
+
+struct BranchAndBound<'a> {
+    scc: &'a SCC,
+    cancel: &'a CancelToken,
+    best: Vec<usize>,
+    bestval: u64,
+    prefix: Vec<usize>,
+    remaining: Vec<bool>,
+    // outcost[r]: cost of placing remaining vertex r after all vertices of the prefix
+    outcost: Vec<u64>,
+    // cycles[v]: the other two vertices and the weight of every packed cycle through v
+    cycles: Vec<Vec<(usize, usize, u64)>>,
+    aborted: bool,
+}
+
+impl BranchAndBound<'_> {
+    // cost: cost within the prefix, pending: sum of outcost over remaining vertices,
+    // rest: sum of min(w[u][v], w[v][u]) over pairs of remaining vertices plus the weight of the
+    // packed cycles among them
+    #[allow(clippy::needless_range_loop)]
+    fn search(&mut self, cost: u64, pending: u64, rest: u64) {
+        if self.cancel.is_cancelled() {
+            self.aborted = true;
+            return;
+        }
+        let n = self.scc.n;
+        if self.prefix.len() == n {
+            if cost < self.bestval {
+                self.bestval = cost;
+                self.best = self.prefix.clone();
+            }
+            return;
+        }
+        let w = &self.scc.w;
+        let mut candidates: Vec<usize> = (0..n).filter(|&v| self.remaining[v]).collect();
+        candidates.sort_by_key(|&v| self.outcost[v]);
+        for v in candidates.iter().cloned() {
+            // an optimal ordering never improves by swapping two adjacent vertices
+            if let Some(&u) = self.prefix.last() {
+                if w[v][u] > w[u][v] {
+                    continue;
+                }
+            }
+            let mut newpending = pending - self.outcost[v];
+            let mut newrest = rest;
+            for r in 0..n {
+                if self.remaining[r] && r != v {
+                    newpending += w[r][v];
+                    newrest -= cmp::min(w[r][v], w[v][r]);
+                }
+            }
+            for &(a, b, m) in self.cycles[v].iter() {
+                if self.remaining[a] && self.remaining[b] {
+                    newrest -= m;
+                }
+            }
+            let newcost = cost + self.outcost[v];
+            if newcost + newpending + newrest >= self.bestval {
+                continue;
+            }
+            self.remaining[v] = false;
+            self.prefix.push(v);
+            for r in 0..n {
+                if self.remaining[r] {
+                    self.outcost[r] += w[r][v];
+                }
+            }
+            self.search(newcost, newpending, newrest);
+            for r in 0..n {
+                if self.remaining[r] {
+                    self.outcost[r] -= w[r][v];
+                }
+            }
+            self.prefix.pop();
+            self.remaining[v] = true;
+            if self.aborted {
+                return;
+            }
+        }
+    }
+}
+
+/// Computes an optimal ordering of `scc`, starting from the ordering `incumbent` and pruning with
+/// the cycles `packing` of `lower_bound::packed_cycles`. Returns the best ordering found, its cost
+/// and whether it is proven optimal (false if `cancel` interrupted the search).
+pub fn branch_and_bound(scc: &SCC, incumbent: Vec<usize>, packing: &[(usize, usize, usize, u64)], cancel: &CancelToken) -> (Vec<usize>, u64, bool) {
+    let bestval = sifting::eval_ordering_scc(&incumbent, scc, cancel);
+    let mut cycles: Vec<Vec<(usize, usize, u64)>> = vec![Vec::new(); scc.n];
+    for &(u, v, x, m) in packing.iter() {
+        cycles[u].push((v, x, m));
+        cycles[v].push((x, u, m));
+        cycles[x].push((u, v, m));
+    }
+    let rest = lower_bound::trivial(scc) + packing.iter().map(|&(_, _, _, m)| m).sum::<u64>();
+    let mut bb = BranchAndBound {
+        scc,
+        cancel,
+        best: incumbent,
+        bestval,
+        prefix: Vec::with_capacity(scc.n),
+        remaining: vec![true; scc.n],
+        outcost: vec![0; scc.n],
+        cycles,
+        aborted: false,
+    };
+    bb.search(0, 0, rest);
+    (bb.best, bb.bestval, !bb.aborted)
+}
+
+/// Strategy solving every SCC exactly. SCCs are processed from small to large so that an
+/// interrupted solve still proves as many of them as possible.
+pub struct Exact {
+    sccs: Vec<SCC>,
+    offset: u64,
+    perm: Vec<Vec<usize>>,
+    packing: Vec<Vec<(usize, usize, usize, u64)>>,
+    // lower bound on the cost of every scc, its cost once it is solved
+    bound: Vec<u64>,
+    optimal: bool,
+}
+
+impl Exact {
+    pub fn new() -> Exact {
+        Exact { sccs: Vec::new(), offset: 0, perm: Vec::new(), packing: Vec::new(), bound: Vec::new(), optimal: false }
+    }
+}
+
+impl Default for Exact {
+    fn default() -> Exact {
+        Exact::new()
+    }
+}
+
+impl Strategy for Exact {
+    fn name(&self) -> &str {
+        "exact"
+    }
+
+    fn prepare(&mut self, ctx: &mut Context) {
+        let (sccs, offset) = ctx.reduce();
+        self.offset = offset;
+        self.packing = sccs.iter().map(|scc| lower_bound::packed_cycles(scc, &ctx.cancel)).collect();
+        self.bound = (0..sccs.len()).map(|i| lower_bound::trivial(&sccs[i]) + self.packing[i].iter().map(|&(_, _, _, m)| m).sum::<u64>()).collect();
+        self.sccs = sccs;
+        self.perm = match ctx.initial {
+            Some(ref ordering) => sifting::split_ordering(ordering, &self.sccs),
+            None => self.sccs.iter().map(|scc| (0..scc.n).collect()).collect(),
+        };
+    }
+
+    fn improve(&mut self, ctx: &mut Context) -> bool {
+        let sccs = &self.sccs;
+        let cancel = &ctx.cancel;
+        // seed with the best of a few sifting runs
+        let mut bestval: Vec<u64> = (0..sccs.len()).map(|i| sifting::eval_ordering_scc(&self.perm[i], &sccs[i], cancel)).collect();
+        let inv_w = match sifting::get_inv_w(sccs, cancel) {
+            Some(inv_w) => inv_w,
+            None => return false,
+        };
+        for _ in 0..SEED_RUNS {
+            let perm = sifting::hillclimber_sifting(sccs, &inv_w, sifting::insertion_sifting(sccs, &inv_w, &mut ctx.rng, cancel), &mut ctx.rng, cancel);
+            if cancel.is_cancelled() {
+                return false;
+            }
+            for i in 0..sccs.len() {
+                let val = sifting::eval_ordering_scc(&perm[i], &sccs[i], cancel);
+                if val < bestval[i] {
+                    bestval[i] = val;
+                    self.perm[i] = perm[i].clone();
+                }
+            }
+        }
+        ctx.phase("branch and bound");
+        let cancel = &ctx.cancel;
+
+        let mut order: Vec<usize> = (0..sccs.len()).collect();
+        order.sort_by_key(|&i| sccs[i].n);
+        self.optimal = true;
+        for i in order.iter().cloned() {
+            if !self.optimal || sccs[i].n == 1 {
+                continue;
+            }
+            let (perm, val, optimal) = branch_and_bound(&sccs[i], self.perm[i].clone(), &self.packing[i], cancel);
+            self.perm[i] = perm;
+            if optimal {
+                self.bound[i] = val;
+            } else {
+                self.optimal = false;
+            }
+        }
+        false
+    }
+
+    fn best(&self) -> Vec<usize> {
+        sifting::map_to_original_labels(&self.perm, &self.sccs)
+    }
+
+    fn lower_bound(&self) -> u64 {
+        self.offset + self.bound.iter().sum::<u64>()
+    }
+
+    fn proved_optimal(&self) -> bool {
+        self.optimal
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::subset_dp;
+    use rand::{Rng, SeedableRng};
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn matches_subset_dp() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let cancel = CancelToken::new();
+        for k in 0..200 {
+            let scc = SCC::random(rng.gen_range(1..=11), k % 2 == 0, &mut rng);
+            let packing = lower_bound::packed_cycles(&scc, &cancel);
+            let (perm, val, optimal) = branch_and_bound(&scc, (0..scc.n).collect(), &packing, &cancel);
+            let (_, expected) = subset_dp::solve(&scc, &cancel).unwrap();
+            assert!(optimal);
+            assert_eq!(val, expected);
+            assert_eq!(sifting::eval_ordering_scc(&perm, &scc, &cancel), expected);
+        }
+    }
+}
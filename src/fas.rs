@@ -0,0 +1,163 @@
+// Minimum weight feedback arc sets of arbitrary digraphs. Every ordering of the vertices yields
+// the feedback arc set of the arcs pointing backwards, so the problem is a linear ordering
+// instance in which placing v before u costs the weight of the arc u -> v.
+use crate::bipartite_graph::{self, ParseError};
+use crate::graph;
+use crate::scc::SCC;
+use crate::sifting;
+use crate::solver::Solver;
+use std::cmp;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+/// A digraph with weighted arcs. Vertices are numbered 0, ..., n-1 in the order of their first
+/// occurrence in the input and keep their names for the output.
+pub struct WeightedDigraph {
+    pub names: Vec<String>,
+    pub arcs: Vec<(usize, usize, u64)>,
+}
+
+impl WeightedDigraph {
+    /// Reads an arc list: one arc `tail head [weight]` per line with arbitrary vertex names and
+    /// weight 1 if none is given. Empty lines and lines starting with `#` are ignored; `c` is a
+    /// vertex name like any other.
+    pub fn from_reader<R: BufRead>(reader: R) -> Result<WeightedDigraph, ParseError> {
+        let mut names: Vec<String> = Vec::new();
+        let mut index: HashMap<String, usize> = HashMap::new();
+        let mut arcs: Vec<(usize, usize, u64)> = Vec::new();
+        let mut lineno: usize = 0;
+        for line in reader.lines() {
+            lineno += 1;
+            let line = line.map_err(|e| ParseError::Io { line: lineno, source: e })?;
+            let ll: Vec<&str> = line.split_whitespace().collect();
+            if ll.is_empty() || ll[0].starts_with('#') {
+                continue;
+            }
+            if ll.len() < 2 || ll.len() > 3 {
+                return Err(ParseError::InvalidEdge { line: lineno });
+            }
+            let mut ends = [0; 2];
+            for k in 0..2 {
+                ends[k] = *index.entry(ll[k].to_string()).or_insert_with(|| {
+                    names.push(ll[k].to_string());
+                    names.len() - 1
+                });
+            }
+            let weight = if ll.len() == 3 { bipartite_graph::parse_number(ll[2], lineno)? as u64 } else { 1 };
+            arcs.push((ends[0], ends[1], weight));
+        }
+        Ok(WeightedDigraph { names, arcs })
+    }
+
+    /// Reads an arc list (see `from_reader`) from the file at `path`.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<WeightedDigraph, ParseError> {
+        let file = File::open(path).map_err(|e| ParseError::Open { source: e })?;
+        WeightedDigraph::from_reader(BufReader::new(file))
+    }
+
+    pub fn n(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Reduces the digraph as `linear_ordering::reduce_matrix` does, but from the arc list: the
+    /// weights of parallel arcs are summed, min(w(u,v), w(v,u)) is subtracted from both directions
+    /// of every pair and dense weights are only built inside the SCCs of the remaining arcs, in
+    /// topological order. Self-loops are in every feedback arc set and therefore left out.
+    pub fn reduce(&self) -> (Vec<SCC>, u64) {
+        let n = self.n();
+        let mut weights: HashMap<(usize, usize), u64> = HashMap::new();
+        for (u, v, weight) in self.arcs.iter().cloned() {
+            if u != v {
+                *weights.entry((u, v)).or_insert(0) += weight;
+            }
+        }
+        let mut offset: u64 = 0;
+        let mut residual: Vec<(usize, usize, u64)> = Vec::new();
+        for (&(u, v), &weight) in weights.iter() {
+            let back = weights.get(&(v, u)).cloned().unwrap_or(0);
+            if u < v {
+                offset += cmp::min(weight, back);
+            }
+            if weight > back {
+                residual.push((u, v, weight - back));
+            }
+        }
+        residual.sort();
+        let mut h: Vec<Vec<usize>> = vec![Vec::new(); n];
+        for (u, v, _) in residual.iter().cloned() {
+            h[u].push(v);
+        }
+        let components = graph::compute_sccs(&h);
+        // component and index within it of every vertex
+        let mut place = vec![(0, 0); n];
+        for (c, component) in components.iter().enumerate() {
+            for (i, &u) in component.iter().enumerate() {
+                place[u] = (c, i);
+            }
+        }
+        let mut sccs: Vec<SCC> = components.into_iter().map(|component| {
+            let k = component.len();
+            SCC::new(component, vec![vec![0; k]; k], vec![Vec::new(); k])
+        }).collect();
+        for (u, v, weight) in residual.iter().cloned() {
+            let (c, i) = place[u];
+            let (d, j) = place[v];
+            if c == d {
+                sccs[c].w[i][j] = weight;
+                sccs[c].g[i].push(j);
+            }
+        }
+        (sccs, offset)
+    }
+
+    /// A solver for the feedback arc set problem; orderings are permutations of 0, ..., n-1 and
+    /// their cost is the weight of their backward arcs without the self-loops.
+    pub fn solver<'a>(&self) -> Solver<'a> {
+        let (sccs, offset) = self.reduce();
+        Solver::reduced(sccs, offset)
+    }
+
+    /// Reorders the vertices of every SCC (see `reduce`) topologically after removing the arcs it
+    /// points backwards (`SCC::ordering_to_fas` and `SCC::fas_to_ordering`). The new ordering
+    /// points no other arc backwards, so its feedback arc set is never heavier, and arcs whose
+    /// reversal closes no cycle may become forward arcs.
+    pub fn tightened(&self, ordering: &[usize]) -> Vec<usize> {
+        let (sccs, _) = self.reduce();
+        let perm: Vec<Vec<usize>> = sifting::split_ordering(ordering, &sccs).iter().enumerate().map(|(i, perm)| {
+            sccs[i].fas_to_ordering(&sccs[i].ordering_to_fas(perm))
+        }).collect();
+        sifting::map_to_original_labels(&perm, &sccs)
+    }
+
+    /// Indices of the arcs that point backwards in `ordering` (including all self-loops).
+    pub fn feedback_arcs(&self, ordering: &[usize]) -> Vec<usize> {
+        let mut pos = vec![0; self.n()];
+        for (i, v) in ordering.iter().cloned().enumerate() {
+            pos[v] = i;
+        }
+        (0..self.arcs.len()).filter(|&a| pos[self.arcs[a].1] <= pos[self.arcs[a].0]).collect()
+    }
+
+    /// Total weight of the self-loops, which every feedback arc set contains.
+    pub fn loop_weight(&self) -> u64 {
+        self.arcs.iter().filter(|&&(u, v, _)| u == v).map(|&(_, _, weight)| weight).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn vertex_named_c_is_not_a_comment() {
+        let g = WeightedDigraph::from_reader("# a triangle\na b 3\nb c\nc a 2\n".as_bytes()).unwrap();
+        assert_eq!(g.names, vec!["a", "b", "c"]);
+        assert_eq!(g.arcs, vec![(0, 1, 3), (1, 2, 1), (2, 0, 2)]);
+        let sol = g.solver().time_limit(Duration::from_secs(1)).seed(0).solve();
+        assert_eq!(sol.crossings, 1);
+        assert_eq!(g.feedback_arcs(&sol.ordering), vec![1]);
+    }
+}
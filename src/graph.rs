@@ -3,7 +3,7 @@
 
 /// Constructs subgraph of g induced by subset. In the resulting graph vertex i corresponds to
 /// vertex subset[i] in the original graph.
-pub fn get_subgraph(g: &Vec<Vec<usize>>, subset: &Vec<usize>) -> Vec<Vec<usize>> {
+pub fn get_subgraph(g: &[Vec<usize>], subset: &[usize]) -> Vec<Vec<usize>> {
     let mut imp: Vec<i32> = vec![-1; g.len()]; 
     for i in 0..subset.len() {
         imp[subset[i]] = i as i32;
@@ -55,6 +55,7 @@ fn propagate_label(g: &Vec<Vec<usize>>, vertex_labels: &mut Vec<usize>, u: usize
 }
 
 /// Returns list of strongly connected components. 
+#[allow(clippy::needless_range_loop)]
 pub fn compute_sccs(h: &Vec<Vec<usize>>) -> Vec<Vec<usize>> {
     // compute top ordering
     let ord = top_ordering(h);
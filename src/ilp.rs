@@ -0,0 +1,184 @@
+// Linear ordering ILP of the reduced instance for general ILP solvers. The binary variable
+// x_i_u_v (u < v) is 1 iff vertex u of the i-th SCC precedes its vertex v, the 3-cycle
+// inequalities 0 <= x_i_u_v + x_i_v_x - x_i_u_x <= 1 make the ordering transitive. SCCs of a single
+// vertex have no variables.
+use crate::bipartite_graph::ParseError;
+use crate::scc::SCC;
+use std::io::{self, BufRead, Write};
+
+// number of objective terms per line of an LP file
+const TERMS_PER_LINE: usize = 8;
+
+fn variable(i: usize, u: usize, v: usize) -> String {
+    format!("x_{}_{}_{}", i, u, v)
+}
+
+fn parse_variable(token: &str) -> Option<(usize, usize, usize)> {
+    let ll: Vec<&str> = token.strip_prefix("x_")?.split('_').collect();
+    if ll.len() != 3 {
+        return None;
+    }
+    Some((ll[0].parse().ok()?, ll[1].parse().ok()?, ll[2].parse().ok()?))
+}
+
+/// The constant which the ILP objective misses: the crossings are the objective value plus this
+/// constant, i.e., `offset` (see `BipartiteGraph::reduce_with_offset`) plus the cost of ordering
+/// every pair of every SCC against its variable.
+pub fn objective_constant(sccs: &[SCC], offset: u64) -> u64 {
+    let mut constant = offset;
+    for scc in sccs.iter() {
+        for u in 0..scc.n {
+            for v in (u+1)..scc.n {
+                constant += scc.w[u][v];
+            }
+        }
+    }
+    constant
+}
+
+// objective coefficient of x_i_u_v: the cost of u before v minus the cost of v before u
+fn coefficient(scc: &SCC, u: usize, v: usize) -> i64 {
+    scc.w[v][u] as i64 - scc.w[u][v] as i64
+}
+
+/// Writes the ILP of `sccs` in the CPLEX LP format.
+pub fn write_lp<W: Write>(sccs: &[SCC], offset: u64, out: &mut W) -> io::Result<()> {
+    writeln!(out, "\\ linear ordering ILP of {} SCCs, crossings = objective + {}", sccs.len(), objective_constant(sccs, offset))?;
+    writeln!(out, "Minimize")?;
+    write!(out, " obj:")?;
+    let mut terms = 0;
+    for (i, scc) in sccs.iter().enumerate() {
+        for u in 0..scc.n {
+            for v in (u+1)..scc.n {
+                let c = coefficient(scc, u, v);
+                if c == 0 {
+                    continue;
+                }
+                if terms > 0 && terms % TERMS_PER_LINE == 0 {
+                    write!(out, "\n    ")?;
+                }
+                write!(out, " {} {} {}", if c < 0 { "-" } else { "+" }, c.abs(), variable(i, u, v))?;
+                terms += 1;
+            }
+        }
+    }
+    writeln!(out)?;
+    writeln!(out, "Subject To")?;
+    for (i, scc) in sccs.iter().enumerate() {
+        for u in 0..scc.n {
+            for v in (u+1)..scc.n {
+                for x in (v+1)..scc.n {
+                    let lhs = format!("{} + {} - {}", variable(i, u, v), variable(i, v, x), variable(i, u, x));
+                    writeln!(out, " t_{}_{}_{}_{}_a: {} <= 1", i, u, v, x, lhs)?;
+                    writeln!(out, " t_{}_{}_{}_{}_b: {} >= 0", i, u, v, x, lhs)?;
+                }
+            }
+        }
+    }
+    writeln!(out, "Binary")?;
+    for (i, scc) in sccs.iter().enumerate() {
+        for u in 0..scc.n {
+            for v in (u+1)..scc.n {
+                writeln!(out, " {}", variable(i, u, v))?;
+            }
+        }
+    }
+    writeln!(out, "End")
+}
+
+/// Writes the ILP of `sccs` in the free MPS format.
+pub fn write_mps<W: Write>(sccs: &[SCC], offset: u64, out: &mut W) -> io::Result<()> {
+    writeln!(out, "* linear ordering ILP of {} SCCs, crossings = objective + {}", sccs.len(), objective_constant(sccs, offset))?;
+    writeln!(out, "NAME sisyphus")?;
+    writeln!(out, "ROWS")?;
+    writeln!(out, " N obj")?;
+    for (i, scc) in sccs.iter().enumerate() {
+        for u in 0..scc.n {
+            for v in (u+1)..scc.n {
+                for x in (v+1)..scc.n {
+                    writeln!(out, " L t_{}_{}_{}_{}_a", i, u, v, x)?;
+                    writeln!(out, " G t_{}_{}_{}_{}_b", i, u, v, x)?;
+                }
+            }
+        }
+    }
+    writeln!(out, "COLUMNS")?;
+    writeln!(out, " MARKER 'MARKER' 'INTORG'")?;
+    for (i, scc) in sccs.iter().enumerate() {
+        let n = scc.n;
+        for u in 0..n {
+            for v in (u+1)..n {
+                let name = variable(i, u, v);
+                writeln!(out, " {} obj {}", name, coefficient(scc, u, v))?;
+                // x_u_v occurs as first, second and third variable of the triples u < v < x,
+                // y < u < v and u < y < v respectively
+                for x in (v+1)..n {
+                    writeln!(out, " {} t_{}_{}_{}_{}_a 1", name, i, u, v, x)?;
+                    writeln!(out, " {} t_{}_{}_{}_{}_b 1", name, i, u, v, x)?;
+                }
+                for y in 0..u {
+                    writeln!(out, " {} t_{}_{}_{}_{}_a 1", name, i, y, u, v)?;
+                    writeln!(out, " {} t_{}_{}_{}_{}_b 1", name, i, y, u, v)?;
+                }
+                for y in (u+1)..v {
+                    writeln!(out, " {} t_{}_{}_{}_{}_a -1", name, i, u, y, v)?;
+                    writeln!(out, " {} t_{}_{}_{}_{}_b -1", name, i, u, y, v)?;
+                }
+            }
+        }
+    }
+    writeln!(out, " MARKER 'MARKER' 'INTEND'")?;
+    writeln!(out, "RHS")?;
+    for (i, scc) in sccs.iter().enumerate() {
+        for u in 0..scc.n {
+            for v in (u+1)..scc.n {
+                for x in (v+1)..scc.n {
+                    writeln!(out, " rhs t_{}_{}_{}_{}_a 1", i, u, v, x)?;
+                }
+            }
+        }
+    }
+    writeln!(out, "BOUNDS")?;
+    for (i, scc) in sccs.iter().enumerate() {
+        for u in 0..scc.n {
+            for v in (u+1)..scc.n {
+                writeln!(out, " BV bnd {}", variable(i, u, v))?;
+            }
+        }
+    }
+    writeln!(out, "ENDATA")
+}
+
+/// Reads the values of the variables from the solution file of an ILP solver and returns the
+/// encoded ordering of each SCC. Every variable name followed by a number is taken as an
+/// assignment (as in the solution files of Gurobi, SCIP, CBC and HiGHS), variables that do not
+/// occur are 0.
+pub fn read_solution<R: BufRead>(sccs: &[SCC], reader: R) -> Result<Vec<Vec<usize>>, ParseError> {
+    let mut value: Vec<Vec<bool>> = sccs.iter().map(|scc| vec![false; scc.n * scc.n.saturating_sub(1) / 2]).collect();
+    let mut lineno: usize = 0;
+    for line in reader.lines() {
+        lineno += 1;
+        let line = line.map_err(|e| ParseError::Io { line: lineno, source: e })?;
+        let ll: Vec<&str> = line.split_whitespace().collect();
+        for k in 1..ll.len() {
+            let (i, u, v) = match parse_variable(ll[k-1]) {
+                Some(var) => var,
+                None => continue,
+            };
+            let x = match ll[k].parse::<f64>() {
+                Ok(x) => x,
+                Err(_) => continue,
+            };
+            if i >= sccs.len() || u >= v || v >= sccs[i].n {
+                return Err(ParseError::InvalidModel { line: lineno });
+            }
+            value[i][sccs[i].pair_index(u, v)] = x > 0.5;
+        }
+    }
+    let mut perm: Vec<Vec<usize>> = Vec::new();
+    for (i, scc) in sccs.iter().enumerate() {
+        let ordering = scc.tournament_to_ordering(|u, v| value[i][scc.pair_index(u, v)]);
+        perm.push(ordering.ok_or(ParseError::InvalidModel { line: lineno })?);
+    }
+    Ok(perm)
+}
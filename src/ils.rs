@@ -0,0 +1,100 @@
+// Iterated local search: instead of starting every hill-climber run from a new insertion
+// ordering, the current local optimum of each SCC is perturbed by a kick (a shuffled segment or a
+// few random reinsertions) and improved again. The kick grows while it fails to find better
+// orderings; an SCC restarts from a new insertion ordering only after a long stagnation.
+use crate::sifting::{self, eval_ordering_scc};
+use crate::local_search::{Incumbents, SccSearch};
+use crate::strategy::Context;
+use rand::Rng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+
+/// Iterations without a new incumbent after which an SCC restarts.
+const STAGNATION: usize = 200;
+
+// perturbs perm by shuffling a random segment of length k+1 or by k random reinsertions
+fn kick(perm: &mut Vec<usize>, k: usize, rng: &mut StdRng) {
+    let n = perm.len();
+    if rng.gen_bool(0.5) {
+        let len = usize::min(n, k + 1);
+        let start = rng.gen_range(0..=(n - len));
+        perm[start..start+len].shuffle(rng);
+    } else {
+        for _ in 0..k {
+            let v = perm.remove(rng.gen_range(0..n));
+            perm.insert(rng.gen_range(0..n), v);
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct IteratedLocalSearch {
+    strength: Vec<usize>,
+    stall: Vec<usize>,
+    restarts: usize,
+}
+
+impl IteratedLocalSearch {
+    fn min_strength(n: usize) -> usize {
+        usize::max(2, n / 100)
+    }
+
+    // kicks beyond a tenth of the SCC hardly differ from restarts, so the strength starts over
+    fn max_strength(n: usize) -> usize {
+        usize::max(IteratedLocalSearch::min_strength(n), n / 10)
+    }
+}
+
+impl SccSearch for IteratedLocalSearch {
+    fn name(&self) -> &str {
+        "ils"
+    }
+
+    fn prepare(&mut self, inc: &mut Incumbents, _inv_w: &[Vec<Vec<u64>>], ctx: &mut Context) {
+        self.strength = inc.sccs.iter().map(|scc| IteratedLocalSearch::min_strength(scc.n)).collect();
+        self.stall = vec![0; inc.sccs.len()];
+        ctx.phase("iterated local search");
+    }
+
+    /// Kicks (or restarts) every open SCC and runs the hill-climber on all of them once.
+    fn improve(&mut self, inc: &mut Incumbents, inv_w: &[Vec<Vec<u64>>], open: &[usize], ctx: &mut Context) -> bool {
+        let sccs = &inc.sccs;
+        let mut perm = inc.current.clone();
+        for i in open.iter().cloned() {
+            if self.stall[i] >= STAGNATION {
+                self.restarts += 1;
+                ctx.restart(self.restarts);
+                perm[i] = sifting::insertion_scc(&sccs[i], &inv_w[i], &mut ctx.rng, &ctx.cancel);
+                inc.current_val[i] = u64::MAX;
+                self.stall[i] = 0;
+                self.strength[i] = IteratedLocalSearch::min_strength(sccs[i].n);
+            } else {
+                kick(&mut perm[i], self.strength[i], &mut ctx.rng);
+            }
+        }
+        let perm = sifting::hillclimber_sifting(sccs, inv_w, perm, &mut ctx.rng, &ctx.cancel);
+        if ctx.cancel.is_cancelled() {
+            return false;
+        }
+        for i in open.iter().cloned() {
+            let n = inc.sccs[i].n;
+            let val = eval_ordering_scc(&perm[i], &inc.sccs[i], &ctx.cancel);
+            // accept orderings that are not worse than the current one
+            if val <= inc.current_val[i] {
+                inc.current[i] = perm[i].clone();
+                inc.current_val[i] = val;
+            }
+            if inc.offer(i, &perm[i], val) {
+                self.stall[i] = 0;
+                self.strength[i] = IteratedLocalSearch::min_strength(n);
+            } else {
+                self.stall[i] += 1;
+                self.strength[i] += self.strength[i] / 4 + 1;
+                if self.strength[i] > IteratedLocalSearch::max_strength(n) {
+                    self.strength[i] = IteratedLocalSearch::min_strength(n);
+                }
+            }
+        }
+        true
+    }
+}
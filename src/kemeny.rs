@@ -0,0 +1,176 @@
+// Kemeny rank aggregation: find the ranking of the alternatives that minimizes the number of
+// pairwise disagreements with the votes. Placing u before v disagrees with every vote that ranks
+// v above u, so the problem is a linear ordering instance with these counts as costs.
+use crate::bipartite_graph::{self, ParseError};
+use crate::linear_ordering::LinearOrdering;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+/// Votes over the alternatives 0, ..., m-1. Each entry of `orders` is a number of voters and
+/// their (possibly incomplete) ranking, most preferred first, as a list of groups of alternatives
+/// that are tied.
+pub struct Profile {
+    pub names: Vec<String>,
+    pub orders: Vec<(u64, Vec<Vec<usize>>)>,
+}
+
+// parses an order "a,{b,c},d" of 1-based alternatives, where braces enclose tied alternatives
+fn parse_order(order: &str, m: usize, line: usize) -> Result<Vec<Vec<usize>>, ParseError> {
+    let mut seen = vec![false; m];
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    let mut tie: Option<Vec<usize>> = None;
+    for token in order.split(',').map(|t| t.trim()) {
+        let opens = token.starts_with('{');
+        let token = token.strip_prefix('{').unwrap_or(token);
+        let closes = token.ends_with('}');
+        let token = token.strip_suffix('}').unwrap_or(token).trim();
+        if (opens && tie.is_some()) || (closes && !opens && tie.is_none()) {
+            return Err(ParseError::InvalidRanking { line });
+        }
+        if opens {
+            tie = Some(Vec::new());
+        }
+        if !token.is_empty() {
+            let a = bipartite_graph::parse_number(token, line)?;
+            if a == 0 || a > m {
+                return Err(ParseError::VertexOutOfRange { line, vertex: a });
+            }
+            if seen[a-1] {
+                return Err(ParseError::InvalidRanking { line });
+            }
+            seen[a-1] = true;
+            match tie {
+                Some(ref mut group) => group.push(a-1),
+                None => groups.push(vec![a-1]),
+            }
+        }
+        if closes {
+            groups.extend(tie.take().filter(|group| !group.is_empty()));
+        }
+    }
+    if tie.is_some() {
+        return Err(ParseError::InvalidRanking { line });
+    }
+    Ok(groups)
+}
+
+impl Profile {
+    /// Reads (possibly incomplete) orders in the PrefLib SOC/SOI/TOC/TOI format, i.e., the
+    /// `# NUMBER ALTERNATIVES: m` and `# ALTERNATIVE NAME i: ...` metadata followed by lines
+    /// `count: a,{b,c},d` with ties in braces. The legacy format (m, m lines `i,name`, a line with
+    /// the numbers of voters and lines `count,a,b,c`) is accepted as well.
+    pub fn from_reader<R: BufRead>(reader: R) -> Result<Profile, ParseError> {
+        let mut m: Option<usize> = None;
+        let mut names: Vec<String> = Vec::new();
+        let mut orders: Vec<(u64, Vec<Vec<usize>>)> = Vec::new();
+        // legacy format: number of lines with alternative names and voter counts still to skip
+        let mut legacy_header: Option<usize> = None;
+        let mut lineno: usize = 0;
+        for line in reader.lines() {
+            lineno += 1;
+            let line = line.map_err(|e| ParseError::Io { line: lineno, source: e })?;
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(meta) = line.strip_prefix('#') {
+                let (key, value) = match meta.split_once(':') {
+                    Some((key, value)) => (key.trim(), value.trim()),
+                    None => continue,
+                };
+                if key == "NUMBER ALTERNATIVES" {
+                    let count = bipartite_graph::parse_number(value, lineno)?;
+                    m = Some(count);
+                    names = (1..=count).map(|i| i.to_string()).collect();
+                } else if let Some(i) = key.strip_prefix("ALTERNATIVE NAME ") {
+                    let i = bipartite_graph::parse_number(i.trim(), lineno)?;
+                    if i == 0 || i > names.len() {
+                        return Err(ParseError::VertexOutOfRange { line: lineno, vertex: i });
+                    }
+                    names[i-1] = value.to_string();
+                }
+                continue;
+            }
+            let m = match m {
+                Some(m) => m,
+                None => {
+                    // the first line of the legacy format is the number of alternatives
+                    let count = bipartite_graph::parse_number(line, lineno)?;
+                    m = Some(count);
+                    names = (1..=count).map(|i| i.to_string()).collect();
+                    legacy_header = Some(count + 1);
+                    continue;
+                }
+            };
+            if let Some(left) = legacy_header {
+                if left > 1 {
+                    if let Some((i, name)) = line.split_once(',') {
+                        let i = bipartite_graph::parse_number(i.trim(), lineno)?;
+                        if i == 0 || i > m {
+                            return Err(ParseError::VertexOutOfRange { line: lineno, vertex: i });
+                        }
+                        names[i-1] = name.trim().to_string();
+                    }
+                }
+                legacy_header = if left > 1 { Some(left - 1) } else { None };
+                continue;
+            }
+            let (count, order) = match line.split_once(':') {
+                Some((count, order)) => (count.trim(), order),
+                None => line.split_once(',').ok_or(ParseError::InvalidOrdering { line: lineno })?,
+            };
+            let count = bipartite_graph::parse_number(count, lineno)? as u64;
+            orders.push((count, parse_order(order, m, lineno)?));
+        }
+        if m.is_none() {
+            return Err(ParseError::MissingHeader { line: lineno });
+        }
+        Ok(Profile { names, orders })
+    }
+
+    /// Reads votes in the PrefLib format (see `from_reader`) from the file at `path`.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Profile, ParseError> {
+        let file = File::open(path).map_err(|e| ParseError::Open { source: e })?;
+        Profile::from_reader(BufReader::new(file))
+    }
+
+    /// Number of alternatives.
+    pub fn m(&self) -> usize {
+        self.names.len()
+    }
+
+    /// The linear ordering instance whose cost is the Kemeny score. Tied alternatives may be
+    /// ordered either way without disagreement; alternatives missing in an incomplete order are
+    /// ranked below the ranked ones and tied among themselves.
+    pub fn linear_ordering(&self) -> LinearOrdering {
+        let m = self.m();
+        let mut lo = LinearOrdering::new(m);
+        let mut ranked = vec![false; m];
+        for (count, order) in self.orders.iter() {
+            for i in 0..order.len() {
+                for j in (i+1)..order.len() {
+                    for u in order[i].iter().cloned() {
+                        for v in order[j].iter().cloned() {
+                            lo.c[v][u] += count;
+                        }
+                    }
+                }
+                for u in order[i].iter().cloned() {
+                    ranked[u] = true;
+                }
+            }
+            for (v, &r) in ranked.iter().enumerate() {
+                if !r {
+                    for u in order.iter().flatten().cloned() {
+                        lo.c[v][u] += count;
+                    }
+                }
+            }
+            for u in order.iter().flatten().cloned() {
+                ranked[u] = false;
+            }
+        }
+        lo
+    }
+}
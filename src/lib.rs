@@ -2,10 +2,33 @@ pub mod bipartite_graph;
 pub mod graph;
 pub mod scc;
 pub mod heuristic;
+pub mod solver;
+pub mod strategy;
+pub mod observer;
 pub mod sifting;
 pub mod sifting_large;
 pub mod sifting_very_large;
-pub mod global_abort;
+pub mod cutwidth_dp;
+pub mod exact;
+pub mod local_search;
+pub mod annealing;
+pub mod ils;
+pub mod memetic;
+pub mod tabu;
+pub mod subset_dp;
+pub mod lower_bound;
+pub mod maxsat;
+pub mod ilp;
+pub mod linear_ordering;
+pub mod fas;
+pub mod lop;
+pub mod kemeny;
+pub mod cancel;
 
 // Re-exports to flatten the crate.
 pub use bipartite_graph::BipartiteGraph as BipartiteGraph;
+pub use bipartite_graph::ParseError as ParseError;
+pub use cancel::CancelToken as CancelToken;
+pub use solver::{Solution, Solver};
+pub use strategy::{Registry, Strategy};
+pub use observer::Event;
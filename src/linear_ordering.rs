@@ -0,0 +1,87 @@
+// Weighted linear ordering instances given by a dense cost matrix. One-sided crossing
+// minimization (with the crossing matrix), feedback arc set, the linear ordering problem and
+// Kemeny rank aggregation are all of this form and share the reduction and the sifting engine.
+use crate::graph;
+use crate::scc::SCC;
+use crate::solver::Solver;
+use std::cmp;
+
+/// Subtracts min(c[u][v], c[v][u]) from both entries of every pair and splits the remaining
+/// instance into its SCCs, in topological order. Returns the SCCs and the sum of the subtracted
+/// minima, which every ordering incurs.
+#[allow(clippy::needless_range_loop)]
+pub fn reduce_matrix(mut cm: Vec<Vec<u64>>) -> (Vec<SCC>, u64) {
+    let mut offset: u64 = 0;
+    for u in 0..cm.len() {
+        for v in u+1..cm.len() {
+            let mn: u64 = cmp::min(cm[u][v], cm[v][u]);
+            cm[u][v] -= mn;
+            cm[v][u] -= mn;
+            offset += mn;
+        }
+    }
+    let mut h: Vec<Vec<usize>> = vec![Vec::new(); cm.len()];
+    for u in 0..cm.len() {
+        for v in 0..cm.len() {
+            if cm[v][u] != 0 {
+                h[u].push(v);
+            }
+        }
+    }
+    let sccs = graph::compute_sccs(&h);
+    let mut result: Vec<SCC> = Vec::new();
+    for scc in sccs.iter().cloned() {
+        let mut w: Vec<Vec<u64>> = vec![vec![0; scc.len()]; scc.len()];
+        let mut g: Vec<Vec<usize>> = vec![Vec::new(); scc.len()];
+        for i in 0..scc.len() {
+            for j in 0..scc.len() {
+                w[i][j] = cm[scc[j]][scc[i]];
+                if w[i][j] != 0 {
+                    g[i].push(j);
+                }
+            }
+        }
+        result.push(SCC::new(scc, w, g));
+    }
+    (result, offset)
+}
+
+/// A weighted linear ordering instance on the vertices 0, ..., n-1, where c[u][v] is the cost of
+/// placing u before v.
+pub struct LinearOrdering {
+    pub c: Vec<Vec<u64>>,
+}
+
+impl LinearOrdering {
+    /// An instance on `n` vertices without costs.
+    pub fn new(n: usize) -> LinearOrdering {
+        LinearOrdering { c: vec![vec![0; n]; n] }
+    }
+
+    pub fn n(&self) -> usize {
+        self.c.len()
+    }
+
+    /// Cost of `ordering`, a permutation of 0, ..., n-1.
+    pub fn cost(&self, ordering: &[usize]) -> u64 {
+        let mut cost = 0;
+        for i in 0..ordering.len() {
+            for j in (i+1)..ordering.len() {
+                cost += self.c[ordering[i]][ordering[j]];
+            }
+        }
+        cost
+    }
+
+    /// See `reduce_matrix`.
+    pub fn reduce(&self) -> (Vec<SCC>, u64) {
+        reduce_matrix(self.c.clone())
+    }
+
+    /// A solver for the instance (with the sifting strategy unless another one is chosen).
+    /// Orderings are permutations of 0, ..., n-1.
+    pub fn solver<'a>(&self) -> Solver<'a> {
+        let (sccs, offset) = self.reduce();
+        Solver::reduced(sccs, offset)
+    }
+}
@@ -0,0 +1,207 @@
+// Shell of the local search strategies (annealing, ils, memetic, tabu). `Incumbents` holds the
+// reduced instance with its small SCCs solved exactly, the best and the current ordering of each
+// SCC and a lower bound on its cost; an SCC whose incumbent meets its bound is closed by renumbering
+// it along the incumbent and marking it optimal, so the hill-climber skips it like the SCCs solved
+// exactly.
+// `LocalSearch` turns an `SccSearch`, which only moves the orderings of the open SCCs, into a
+// `Strategy`.
+use crate::lower_bound;
+use crate::scc::SCC;
+use crate::sifting::{self, eval_ordering_scc};
+use crate::strategy::{Context, Strategy};
+use std::time::Duration;
+
+pub struct Incumbents {
+    pub sccs: Vec<SCC>,
+    pub offset: u64,
+    pub perm: Vec<Vec<usize>>,
+    pub val: Vec<u64>,
+    /// Ordering the search is at in each SCC and its cost, initially the incumbent.
+    pub current: Vec<Vec<usize>>,
+    pub current_val: Vec<u64>,
+    bound: Vec<u64>,
+}
+
+impl Incumbents {
+    pub fn new() -> Incumbents {
+        Incumbents { sccs: Vec::new(), offset: 0, perm: Vec::new(), val: Vec::new(), current: Vec::new(), current_val: Vec::new(), bound: Vec::new() }
+    }
+
+    /// Reduces the graph of `ctx`, solves its small SCCs exactly and starts from the warm start
+    /// or, without one, from one hill-climber run on a random insertion ordering. The bounds get
+    /// the same budget as in sifting: a tenth of its first phase for the packings and as much for
+    /// the lp relaxations. Returns the inverse weights of the SCCs (see `sifting::get_inv_w`), or
+    /// None if the solve was cancelled before they were complete.
+    pub fn prepare(&mut self, ctx: &mut Context) -> Option<Vec<Vec<Vec<u64>>>> {
+        let (mut sccs, offset) = ctx.reduce();
+        sifting::solve_small_sccs(&mut sccs, ctx.dp_threshold, &ctx.cancel);
+        let budget = Duration::from_secs_f64(ctx.time_limit.map_or(60.0, |limit| limit.as_secs_f64() / 5.0) / 10.0);
+        let bound_cancel = ctx.cancel.child();
+        bound_cancel.cancel_after(budget);
+        let mut bound = lower_bound::scc_bounds(&sccs, &bound_cancel);
+        let inv_w = sifting::get_inv_w(&sccs, &ctx.cancel);
+        let perm = match (&ctx.initial, &inv_w) {
+            (Some(ordering), _) => sifting::split_ordering(ordering, &sccs),
+            (None, Some(inv_w)) => sifting::insertion_sifting(&sccs, inv_w, &mut ctx.rng, &ctx.cancel),
+            (None, None) => sccs.iter().map(|scc| (0..scc.n).collect()).collect(),
+        };
+        let perm = match inv_w {
+            Some(ref inv_w) => sifting::hillclimber_sifting(&sccs, inv_w, perm, &mut ctx.rng, &ctx.cancel),
+            None => perm,
+        };
+        self.val = (0..sccs.len()).map(|i| eval_ordering_scc(&perm[i], &sccs[i], &ctx.cancel)).collect();
+        let bound_cancel = ctx.cancel.child();
+        bound_cancel.cancel_after(budget);
+        lower_bound::raise_by_lp(&sccs, &self.val, &mut bound, &bound_cancel);
+        self.bound = bound;
+        self.current = perm.clone();
+        self.current_val = self.val.clone();
+        self.perm = perm;
+        self.sccs = sccs;
+        self.offset = offset;
+        for i in 0..self.sccs.len() {
+            self.close(i);
+        }
+        inv_w
+    }
+
+    // closes scc i if its incumbent meets its bound. The inverse weights of a closed scc no longer
+    // match it, but they are not used anymore.
+    fn close(&mut self, i: usize) {
+        if self.sccs[i].optimal || self.val[i] > self.bound[i] {
+            return;
+        }
+        self.sccs[i] = self.sccs[i].permuted(&self.perm[i]);
+        self.sccs[i].optimal = true;
+        self.perm[i] = (0..self.sccs[i].n).collect();
+        self.current[i] = self.perm[i].clone();
+        self.current_val[i] = self.val[i];
+    }
+
+    /// Indices of the SCCs that are worth searching: neither solved exactly nor closed.
+    pub fn open(&self) -> Vec<usize> {
+        (0..self.sccs.len()).filter(|&i| self.is_open(i)).collect()
+    }
+
+    pub fn is_open(&self, i: usize) -> bool {
+        !self.sccs[i].optimal
+    }
+
+    /// Replaces the incumbent of SCC `i` by `perm` of cost `val` if that is better.
+    pub fn offer(&mut self, i: usize, perm: &[usize], val: u64) -> bool {
+        if val < self.val[i] {
+            self.val[i] = val;
+            self.perm[i] = perm.to_vec();
+            self.close(i);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Offers the current ordering of SCC `i`.
+    pub fn accept(&mut self, i: usize) -> bool {
+        if self.current_val[i] < self.val[i] {
+            self.val[i] = self.current_val[i];
+            self.perm[i] = self.current[i].clone();
+            self.close(i);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Moves the search in SCC `i` back to the incumbent.
+    pub fn reset(&mut self, i: usize) {
+        self.current[i] = self.perm[i].clone();
+        self.current_val[i] = self.val[i];
+    }
+
+    pub fn best(&self) -> Vec<usize> {
+        sifting::map_to_original_labels(&self.perm, &self.sccs)
+    }
+
+    /// The crossings fixed by the reduction plus the bounds of the SCCs.
+    pub fn lower_bound(&self) -> u64 {
+        self.offset + self.bound.iter().sum::<u64>()
+    }
+
+    pub fn proved_optimal(&self) -> bool {
+        self.open().is_empty()
+    }
+}
+
+impl Default for Incumbents {
+    fn default() -> Incumbents {
+        Incumbents::new()
+    }
+}
+
+/// A search over the orderings of the SCCs of `Incumbents`.
+pub trait SccSearch {
+    fn name(&self) -> &str;
+
+    /// Sets up the search once `inc` is prepared. `inv_w` are the inverse weights of its SCCs.
+    fn prepare(&mut self, inc: &mut Incumbents, inv_w: &[Vec<Vec<u64>>], ctx: &mut Context);
+
+    /// Runs one round of the search in the SCCs `open` and offers what it finds to `inc`. Returns
+    /// false if the round was cancelled.
+    fn improve(&mut self, inc: &mut Incumbents, inv_w: &[Vec<Vec<u64>>], open: &[usize], ctx: &mut Context) -> bool;
+}
+
+/// The strategy running `S` on the open SCCs until all of them are closed.
+pub struct LocalSearch<S> {
+    inc: Incumbents,
+    // None if the solve was cancelled before the inverse weights were complete
+    inv_w: Option<Vec<Vec<Vec<u64>>>>,
+    search: S,
+}
+
+impl<S: SccSearch + Default> LocalSearch<S> {
+    pub fn new() -> LocalSearch<S> {
+        LocalSearch { inc: Incumbents::new(), inv_w: None, search: S::default() }
+    }
+}
+
+impl<S: SccSearch + Default> Default for LocalSearch<S> {
+    fn default() -> LocalSearch<S> {
+        LocalSearch::new()
+    }
+}
+
+impl<S: SccSearch> Strategy for LocalSearch<S> {
+    fn name(&self) -> &str {
+        self.search.name()
+    }
+
+    fn prepare(&mut self, ctx: &mut Context) {
+        self.inv_w = self.inc.prepare(ctx);
+        if let Some(ref inv_w) = self.inv_w {
+            self.search.prepare(&mut self.inc, inv_w, ctx);
+        }
+    }
+
+    fn improve(&mut self, ctx: &mut Context) -> bool {
+        let inv_w = match self.inv_w {
+            Some(ref inv_w) => inv_w,
+            None => return false,
+        };
+        let open = self.inc.open();
+        if open.is_empty() {
+            return false;
+        }
+        self.search.improve(&mut self.inc, inv_w, &open, ctx)
+    }
+
+    fn best(&self) -> Vec<usize> {
+        self.inc.best()
+    }
+
+    fn lower_bound(&self) -> u64 {
+        self.inc.lower_bound()
+    }
+
+    fn proved_optimal(&self) -> bool {
+        self.inc.proved_optimal()
+    }
+}
@@ -0,0 +1,90 @@
+// The linear ordering problem: find a permutation maximizing the sum of the matrix entries above
+// the diagonal, a[p(i)][p(j)] for i < j. For every pair exactly one of a[u][v] and a[v][u] counts,
+// so the problem is to minimize the loss max(a[u][v], a[v][u]) - a[u][v] of placing u before v.
+use crate::bipartite_graph::ParseError;
+use crate::linear_ordering::LinearOrdering;
+use std::cmp;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+pub struct LopInstance {
+    pub a: Vec<Vec<i64>>,
+}
+
+impl LopInstance {
+    /// Reads a matrix in the LOLIB/xLOLIB format: the dimension n followed by the n*n entries in
+    /// row-major order, separated by arbitrary whitespace. Leading lines that do not start with a
+    /// number (instance names or comments) are skipped.
+    pub fn from_reader<R: BufRead>(reader: R) -> Result<LopInstance, ParseError> {
+        let mut numbers: Vec<i64> = Vec::new();
+        let mut n: Option<usize> = None;
+        let mut lineno: usize = 0;
+        for line in reader.lines() {
+            lineno += 1;
+            let line = line.map_err(|e| ParseError::Io { line: lineno, source: e })?;
+            let ll: Vec<&str> = line.split_whitespace().collect();
+            if ll.is_empty() || (n.is_none() && ll[0].parse::<i64>().is_err()) {
+                continue;
+            }
+            for token in ll.iter() {
+                let x = token.parse::<i64>().map_err(|_| ParseError::InvalidNumber { line: lineno, token: token.to_string() })?;
+                match n {
+                    None if x < 0 => return Err(ParseError::InvalidNumber { line: lineno, token: token.to_string() }),
+                    None => n = Some(x as usize),
+                    Some(_) => numbers.push(x),
+                }
+            }
+        }
+        let n = n.ok_or(ParseError::MissingHeader { line: lineno })?;
+        if numbers.len() != n * n {
+            return Err(ParseError::MissingEntries { line: lineno, expected: n * n, found: numbers.len() });
+        }
+        Ok(LopInstance { a: numbers.chunks(n.max(1)).map(|row| row.to_vec()).take(n).collect() })
+    }
+
+    /// Reads a matrix in the LOLIB format (see `from_reader`) from the file at `path`.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<LopInstance, ParseError> {
+        let file = File::open(path).map_err(|e| ParseError::Open { source: e })?;
+        LopInstance::from_reader(BufReader::new(file))
+    }
+
+    pub fn n(&self) -> usize {
+        self.a.len()
+    }
+
+    /// The sum of max(a[u][v], a[v][u]) over all pairs, the objective if no entry is lost.
+    pub fn max_objective(&self) -> i64 {
+        let mut total = 0;
+        for u in 0..self.n() {
+            for v in (u+1)..self.n() {
+                total += cmp::max(self.a[u][v], self.a[v][u]);
+            }
+        }
+        total
+    }
+
+    /// The linear ordering instance whose cost is the loss `max_objective() - objective()`.
+    pub fn linear_ordering(&self) -> LinearOrdering {
+        let mut lo = LinearOrdering::new(self.n());
+        for u in 0..self.n() {
+            for v in 0..self.n() {
+                if u != v {
+                    lo.c[u][v] = (cmp::max(self.a[u][v], self.a[v][u]) - self.a[u][v]) as u64;
+                }
+            }
+        }
+        lo
+    }
+
+    /// Sum of a[p(i)][p(j)] over i < j for the permutation p given by `ordering`.
+    pub fn objective(&self, ordering: &[usize]) -> i64 {
+        let mut objective = 0;
+        for i in 0..ordering.len() {
+            for j in (i+1)..ordering.len() {
+                objective += self.a[ordering[i]][ordering[j]];
+            }
+        }
+        objective
+    }
+}
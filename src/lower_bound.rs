@@ -0,0 +1,249 @@
+// Lower bounds on the number of crossings. Every ordering of an SCC pays at least
+// min(w[u][v], w[v][u]) for each pair and, for each directed 3-cycle of SCC::g, at least one of
+// its arcs. A packing of 3-cycles whose weights do not exceed the arc weights therefore bounds
+// the cost of the SCC from below.
+use crate::BipartiteGraph;
+use crate::scc::SCC;
+use crate::sifting::eval_ordering_scc;
+use crate::cancel::CancelToken;
+use std::cmp;
+use std::collections::HashSet;
+
+/// Sum of min(w[u][v], w[v][u]) over all pairs of vertices of `scc`.
+pub fn trivial(scc: &SCC) -> u64 {
+    let mut bound = 0;
+    for u in 0..scc.n {
+        for v in (u+1)..scc.n {
+            bound += cmp::min(scc.w[u][v], scc.w[v][u]);
+        }
+    }
+    bound
+}
+
+/// Greedily packs weighted 3-cycles of `scc.g` into the arc weights (without the pairwise minima)
+/// and returns the total weight of the packing. If `cancel` interrupts the computation, the
+/// packing found so far is returned, which is still a valid bound.
+pub fn cycle_packing(scc: &SCC, cancel: &CancelToken) -> u64 {
+    packed_cycles(scc, cancel).iter().map(|&(_, _, _, m)| m).sum()
+}
+
+/// The packing of `cycle_packing` as cycles (u, v, x, m) of weight m. The cycles within any subset
+/// of the vertices bound the cost of ordering that subset.
+#[allow(clippy::needless_range_loop)]
+pub fn packed_cycles(scc: &SCC, cancel: &CancelToken) -> Vec<(usize, usize, usize, u64)> {
+    let n = scc.n;
+    // residual weight of the arc u -> v
+    let mut res: Vec<Vec<u64>> = vec![vec![0; n]; n];
+    for u in 0..n {
+        for v in 0..n {
+            res[u][v] = scc.w[u][v] - cmp::min(scc.w[u][v], scc.w[v][u]);
+        }
+    }
+    let mut packing = Vec::new();
+    for u in 0..n {
+        for v in scc.g[u].iter().cloned() {
+            // only cycles through u with u as smallest vertex are enumerated from u
+            if v < u {
+                continue;
+            }
+            // every arc u -> v scans the out-arcs of v, so dense sccs need the check here
+            if cancel.is_cancelled() {
+                return packing;
+            }
+            for x in scc.g[v].iter().cloned() {
+                if res[u][v] == 0 {
+                    break;
+                }
+                if x < u || res[v][x] == 0 || res[x][u] == 0 {
+                    continue;
+                }
+                let m = cmp::min(res[u][v], cmp::min(res[v][x], res[x][u]));
+                res[u][v] -= m;
+                res[v][x] -= m;
+                res[x][u] -= m;
+                packing.push((u, v, x, m));
+            }
+        }
+    }
+    packing
+}
+
+/// Lower bound on the cost of any ordering of `scc`.
+pub fn scc_bound(scc: &SCC, cancel: &CancelToken) -> u64 {
+    if scc.n < 3 {
+        return trivial(scc);
+    }
+    trivial(scc) + cycle_packing(scc, cancel)
+}
+
+/// Bounds on the costs of `sccs`: the cost of the identity for the SCCs solved exactly and
+/// `scc_bound` for the others. `cancel` only interrupts the packings.
+pub fn scc_bounds(sccs: &[SCC], cancel: &CancelToken) -> Vec<u64> {
+    sccs.iter().map(|scc| if scc.optimal {
+        eval_ordering_scc(&(0..scc.n).collect::<Vec<usize>>(), scc, &CancelToken::new())
+    } else {
+        scc_bound(scc, cancel)
+    }).collect()
+}
+
+/// Raises `bounds` by `lp_bound` for the SCCs whose incumbent costs `upper` exceed them.
+pub fn raise_by_lp(sccs: &[SCC], upper: &[u64], bounds: &mut [u64], cancel: &CancelToken) {
+    for i in 0..sccs.len() {
+        if sccs[i].optimal || upper[i] == bounds[i] {
+            continue;
+        }
+        bounds[i] = cmp::max(bounds[i], lp_bound(&sccs[i], upper[i], cancel));
+    }
+}
+
+/// Lower bound on the number of crossings of any ordering of the free vertices of `g`: the
+/// crossings already fixed by `BipartiteGraph::reduce` plus the bounds of all SCCs.
+pub fn lower_bound(g: &BipartiteGraph, cancel: &CancelToken) -> u64 {
+    let (sccs, offset) = g.reduce_with_offset();
+    offset + sccs.iter().map(|scc| scc_bound(scc, cancel)).sum::<u64>()
+}
+
+/// Largest SCC for which `lp_bound` is computed.
+pub const LP_MAX_SIZE: usize = 300;
+
+const LP_ITERATIONS: usize = 2000;
+
+// separation of 3-cycle inequalities is O(n^3) and only done every few iterations
+const LP_SEPARATION_INTERVAL: usize = 10;
+
+/// Bound from the linear ordering relaxation of `scc` with 3-cycle inequalities
+/// x_pq + x_qr + x_rp <= 2. The inequalities are added when violated by the minimizer of the
+/// Lagrangian relaxation, whose multipliers are improved by subgradient steps towards `upper`, the
+/// cost of a known ordering. Every iterate is a valid bound, so an interrupted computation returns
+/// the best bound found so far.
+pub fn lp_bound(scc: &SCC, upper: u64, cancel: &CancelToken) -> u64 {
+    let n = scc.n;
+    let trivial = trivial(scc);
+    if !(3..=LP_MAX_SIZE).contains(&n) {
+        return trivial;
+    }
+    // cost of placing p before q
+    let cost = |p: usize, q: usize| scc.w[q][p] as f64;
+    let mut cycles: Vec<(usize, usize, usize)> = Vec::new();
+    let mut known: HashSet<(usize, usize, usize)> = HashSet::new();
+    let mut lambda: Vec<f64> = Vec::new();
+    // arc[p][q]: sum of the multipliers of the cycles containing the arc p -> q
+    let mut arc: Vec<Vec<f64>> = vec![vec![0.0; n]; n];
+    let mut before: Vec<Vec<bool>> = vec![vec![false; n]; n];
+    let mut best = trivial as f64;
+    let mut mu = 2.0;
+    let mut stall = 0;
+    let mut separate = true;
+    for iter in 0..LP_ITERATIONS {
+        if cancel.is_cancelled() {
+            break;
+        }
+        // minimize the Lagrangian, pair by pair
+        let mut val = -2.0 * lambda.iter().sum::<f64>();
+        for u in 0..n {
+            for v in (u+1)..n {
+                let uv = cost(u, v) + arc[u][v];
+                let vu = cost(v, u) + arc[v][u];
+                before[u][v] = uv <= vu;
+                before[v][u] = !before[u][v];
+                val += if before[u][v] { uv } else { vu };
+            }
+        }
+        if val > best {
+            best = val;
+            stall = 0;
+        } else {
+            stall += 1;
+            if stall == 20 {
+                mu /= 2.0;
+                stall = 0;
+            }
+        }
+        if (upper as f64) - best < 1.0 {
+            break;
+        }
+        let separated = separate || iter % LP_SEPARATION_INTERVAL == 0;
+        if separated {
+            let added = cycles.len();
+            'separation: for u in 0..n {
+                for v in (u+1)..n {
+                    for x in (v+1)..n {
+                        let cycle = if before[u][v] && before[v][x] && before[x][u] {
+                            (u, v, x)
+                        } else if before[u][x] && before[x][v] && before[v][u] {
+                            (u, x, v)
+                        } else {
+                            continue;
+                        };
+                        if known.insert(cycle) {
+                            cycles.push(cycle);
+                            lambda.push(0.0);
+                            if cycles.len() - added == n {
+                                break 'separation;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        // subgradient of the multipliers
+        let mut subgradient: Vec<f64> = Vec::with_capacity(cycles.len());
+        let mut norm = 0.0;
+        for (c, &(p, q, r)) in cycles.iter().enumerate() {
+            let mut g = before[p][q] as u8 as f64 + before[q][r] as u8 as f64 + before[r][p] as u8 as f64 - 2.0;
+            if g < 0.0 && lambda[c] == 0.0 {
+                g = 0.0;
+            }
+            norm += g * g;
+            subgradient.push(g);
+        }
+        if norm == 0.0 {
+            // without violated inequalities the minimizer is an optimal ordering
+            if separated {
+                break;
+            }
+            separate = true;
+            continue;
+        }
+        separate = false;
+        let step = mu * ((upper as f64) - val) / norm;
+        for (c, &(p, q, r)) in cycles.iter().enumerate() {
+            let newlambda = f64::max(0.0, lambda[c] + step * subgradient[c]);
+            let delta = newlambda - lambda[c];
+            lambda[c] = newlambda;
+            arc[p][q] += delta;
+            arc[q][r] += delta;
+            arc[r][p] += delta;
+        }
+    }
+    // costs are integral, allow for rounding errors of the floating point sums
+    let bound = (best - 1e-6 * f64::max(1.0, best)).ceil().max(0.0) as u64;
+    cmp::max(trivial, cmp::min(bound, upper))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sifting;
+    use crate::subset_dp;
+    use rand::{Rng, SeedableRng};
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn bounds_never_exceed_the_optimum() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let cancel = CancelToken::new();
+        for k in 0..200 {
+            let scc = SCC::random(rng.gen_range(1..=12), k % 2 == 0, &mut rng);
+            let (_, opt) = subset_dp::solve(&scc, &cancel).unwrap();
+            let bound = scc_bound(&scc, &cancel);
+            assert!(trivial(&scc) <= bound && bound <= opt, "scc_bound {} > optimum {}", bound, opt);
+            // the lp bound with the optimum and with a worse ordering as upper bound
+            let identity = sifting::eval_ordering_scc(&(0..scc.n).collect::<Vec<usize>>(), &scc, &cancel);
+            for upper in [opt, identity] {
+                let lp = lp_bound(&scc, upper, &cancel);
+                assert!(lp <= opt, "lp_bound {} > optimum {}", lp, opt);
+            }
+        }
+    }
+}
@@ -1,9 +1,175 @@
-use sisyphus::bipartite_graph::BipartiteGraph;
-use sisyphus::heuristic;
+use sisyphus::bipartite_graph::{self, BipartiteGraph};
+use sisyphus::fas::WeightedDigraph;
+use sisyphus::lop::LopInstance;
+use sisyphus::kemeny::Profile;
+use sisyphus::{CancelToken, Registry, Solution, Solver};
+use signal_hook::{iterator::Signals, consts::signal::*};
+use std::env;
+use std::io;
+use std::process;
+use std::thread;
+use std::time::Duration;
+
+fn fail(msg: String) -> ! {
+    eprintln!("c {}", msg);
+    process::exit(1);
+}
+
+// Options of the command line that apply to every input format.
+struct Options {
+    time_limit: Option<Duration>,
+    seed: u64,
+    strategy: Option<String>,
+    dp_threshold: Option<usize>,
+    progress: bool,
+}
+
+fn configure<'a>(mut solver: Solver<'a>, options: &Options, registry: &Registry, cancel: &CancelToken) -> Solver<'a> {
+    solver = solver.seed(options.seed).cancel_token(cancel.clone());
+    if let Some(limit) = options.time_limit {
+        solver = solver.time_limit(limit);
+    }
+    if let Some(ref name) = options.strategy {
+        solver = solver.strategy(registry.create(name).unwrap());
+    }
+    if let Some(n) = options.dp_threshold {
+        solver = solver.dp_threshold(n);
+    }
+    if options.progress {
+        solver = solver.observer(|event| eprintln!("c {}", event));
+    }
+    solver
+}
+
+fn summary(sol: &Solution) -> String {
+    format!("{} runs in {:.1}s{}", sol.runs, sol.elapsed.as_secs_f64(), if sol.proved_optimal { " (optimal)" } else { "" })
+}
+
+// Breaks all cycles of a weighted digraph read from stdin and prints the removed arcs.
+fn solve_fas(options: &Options, registry: &Registry, cancel: &CancelToken) {
+    let g = WeightedDigraph::from_reader(io::stdin().lock()).unwrap_or_else(|e| fail(format!("Failed to read the arcs: {}", e)));
+    let sol = configure(g.solver(), options, registry, cancel).solve();
+    let removed = g.feedback_arcs(&g.tightened(&sol.ordering));
+    let weight: u64 = removed.iter().map(|&a| g.arcs[a].2).sum();
+    eprintln!("c {}: {} arcs of weight {}, lower bound {}, {}", sol.strategy_used, removed.len(), weight, sol.lower_bound + g.loop_weight(), summary(&sol));
+    for a in removed.iter().cloned() {
+        let (u, v, w) = g.arcs[a];
+        println!("{} {} {}", g.names[u], g.names[v], w);
+    }
+}
+
+// Orders the rows of a LOLIB matrix read from stdin and prints the permutation (1-based).
+fn solve_lop(options: &Options, registry: &Registry, cancel: &CancelToken) {
+    let lop = LopInstance::from_reader(io::stdin().lock()).unwrap_or_else(|e| fail(format!("Failed to read the matrix: {}", e)));
+    let sol = configure(lop.linear_ordering().solver(), options, registry, cancel).solve();
+    eprintln!("c {}: objective {}, upper bound {}, {}", sol.strategy_used, lop.objective(&sol.ordering), lop.max_objective() - sol.lower_bound as i64, summary(&sol));
+    for u in &sol.ordering { println!("{}", u + 1); }
+}
+
+// Aggregates the PrefLib votes read from stdin and prints the consensus ranking (1-based, with
+// the names of the alternatives).
+fn solve_kemeny(options: &Options, registry: &Registry, cancel: &CancelToken) {
+    let profile = Profile::from_reader(io::stdin().lock()).unwrap_or_else(|e| fail(format!("Failed to read the votes: {}", e)));
+    let sol = configure(profile.linear_ordering().solver(), options, registry, cancel).solve();
+    eprintln!("c {}: score {}, lower bound {}, {}", sol.strategy_used, sol.crossings, sol.lower_bound, summary(&sol));
+    for u in sol.ordering.iter().cloned() { println!("{} {}", u + 1, profile.names[u]); }
+}
+
+// Input formats besides PACE graphs.
+enum Mode {
+    Ocm,
+    Fas,
+    Lop,
+    Kemeny,
+}
 
 fn main() {
+    // Parse the command line: an optional previous solution to start from, a time limit, a seed
+    // a strategy, the size up to which SCCs are solved exactly and whether to report progress.
+    // With --fas, --lop or --kemeny, the input is a weighted arc list, a LOLIB matrix or PrefLib
+    // votes instead of a PACE graph.
+    let registry = Registry::default();
+    let mut initial_path: Option<String> = None;
+    let mut time_limit: Option<Duration> = None;
+    let mut seed: Option<u64> = None;
+    let mut strategy: Option<String> = None;
+    let mut progress = false;
+    let mut dp_threshold: Option<usize> = None;
+    let mut mode = Mode::Ocm;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--initial" => initial_path = Some(args.next().unwrap_or_else(|| fail("--initial expects a file".to_string()))),
+            "--time-limit" => {
+                let secs = args.next().and_then(|s| s.parse::<f64>().ok()).filter(|s| s.is_finite() && *s >= 0.0);
+                time_limit = Some(Duration::from_secs_f64(secs.unwrap_or_else(|| fail("--time-limit expects a number of seconds".to_string()))));
+            },
+            "--seed" => seed = Some(args.next().and_then(|s| s.parse::<u64>().ok()).unwrap_or_else(|| fail("--seed expects an unsigned integer".to_string()))),
+            "--strategy" => {
+                let name = args.next().unwrap_or_default();
+                if registry.create(&name).is_none() {
+                    fail(format!("--strategy expects one of: {}", registry.names().join(", ")));
+                }
+                strategy = Some(name);
+            },
+            "--progress" => progress = true,
+            "--dp-threshold" => dp_threshold = Some(args.next().and_then(|s| s.parse::<usize>().ok()).unwrap_or_else(|| fail("--dp-threshold expects a number of vertices".to_string()))),
+            "--exact" => strategy = Some("exact".to_string()),
+            "--fas" => mode = Mode::Fas,
+            "--lop" => mode = Mode::Lop,
+            "--kemeny" => mode = Mode::Kemeny,
+            _ => fail(format!("Unknown argument '{}'", arg)),
+        }
+    }
+
+    // Report the seed so that the run can be reproduced.
+    let seed = seed.unwrap_or_else(rand::random);
+    eprintln!("c seed {}", seed);
+    let options = Options { time_limit, seed, strategy, dp_threshold, progress };
+
+    // The other formats are solved on the reduced instance, without a graph and its ids.
+    if !matches!(mode, Mode::Ocm) {
+        if initial_path.is_some() {
+            fail("--initial is only supported for PACE graphs".to_string());
+        }
+        if let Some(ref name) = options.strategy {
+            if registry.create(name).unwrap().needs_graph() {
+                fail(format!("--strategy {} is only supported for PACE graphs", name));
+            }
+        }
+    }
+
+    // Stop the search on SIGTERM as required by PACE. SIGINT helps to test via CTRL-C.
+    let cancel = CancelToken::new();
+    let mut signals = Signals::new([SIGINT, SIGTERM]).unwrap_or_else(|e| fail(format!("Failed to install signal handler: {}", e)));
+    let handler_token = cancel.clone();
+    thread::spawn(move || {
+        for _sig in signals.forever() {
+            handler_token.cancel();
+        }
+    });
+
+    match mode {
+        Mode::Fas => return solve_fas(&options, &registry, &cancel),
+        Mode::Lop => return solve_lop(&options, &registry, &cancel),
+        Mode::Kemeny => return solve_kemeny(&options, &registry, &cancel),
+        Mode::Ocm => (),
+    }
+
     // Solve the problem using the given strategy.
-    let g  = BipartiteGraph::new_from_stdin().expect("c Failed to read the graph!");    
-    let res = heuristic::start(&g);
-    for u in &res { println!("{}", u); }    
+    let g = BipartiteGraph::new_from_stdin().unwrap_or_else(|e| fail(format!("Failed to read the graph: {}", e)));
+    let initial = initial_path.map(|path| {
+        let ordering = bipartite_graph::read_ordering_from_path(&path).unwrap_or_else(|e| fail(format!("Failed to read the initial ordering: {}", e)));
+        if !g.is_valid_ordering(&ordering) {
+            fail(format!("The initial ordering is not a permutation of {}, ..., {}", g.n0 + 1, g.n0 + g.num_free_vertices()));
+        }
+        ordering
+    });
+    let mut solver = configure(Solver::new(&g), &options, &registry, &cancel);
+    if let Some(ordering) = initial {
+        solver = solver.initial(&ordering);
+    }
+    let sol = solver.solve();
+    eprintln!("c {}: {} crossings, lower bound {}, gap {} ({:.2}%), {}", sol.strategy_used, sol.crossings, sol.lower_bound, sol.gap(), sol.relative_gap(), summary(&sol));
+    for u in &sol.ordering { println!("{}", u); }
 }
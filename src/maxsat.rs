@@ -0,0 +1,90 @@
+// Weighted MaxSAT encoding of the ordering problem of an SCC, to cross-check sisyphus against
+// MaxSAT solvers. Variable pair_index(u, v) + 1 is true iff vertex u precedes vertex v (u < v).
+// Hard clauses forbid the two orientations of a directed 3-cycle on every triple, soft clauses
+// charge w[v][u] if u precedes v and w[u][v] otherwise.
+use crate::bipartite_graph::{self, ParseError};
+use crate::scc::SCC;
+use std::io::{self, BufRead, Write};
+
+fn variable(scc: &SCC, u: usize, v: usize) -> i64 {
+    scc.pair_index(u, v) as i64 + 1
+}
+
+/// Writes the MaxSAT instance of `scc` in the WCNF format with a p-line
+/// (`p wcnf <variables> <clauses> <top>`), hard clauses carry the weight top.
+pub fn write_wcnf<W: Write>(scc: &SCC, out: &mut W) -> io::Result<()> {
+    let n = scc.n;
+    let mut soft: Vec<(u64, i64)> = Vec::new();
+    for u in 0..n {
+        for v in (u+1)..n {
+            let x = variable(scc, u, v);
+            if scc.w[v][u] > 0 {
+                soft.push((scc.w[v][u], -x));
+            }
+            if scc.w[u][v] > 0 {
+                soft.push((scc.w[u][v], x));
+            }
+        }
+    }
+    let nvars = n * n.saturating_sub(1) / 2;
+    let nhard = if n < 3 { 0 } else { n * (n - 1) * (n - 2) / 3 };
+    let top = soft.iter().map(|&(weight, _)| weight).sum::<u64>() + 1;
+    writeln!(out, "c ordering of an SCC with {} vertices, labels {:?}", n, scc.labels)?;
+    writeln!(out, "c variable u*(2n-u-1)/2 + v-u for u < v is true iff u precedes v (0-based)")?;
+    writeln!(out, "p wcnf {} {} {}", nvars, nhard + soft.len(), top)?;
+    for u in 0..n {
+        for v in (u+1)..n {
+            for x in (v+1)..n {
+                let (uv, vx, ux) = (variable(scc, u, v), variable(scc, v, x), variable(scc, u, x));
+                // neither u -> v -> x -> u nor u -> x -> v -> u
+                writeln!(out, "{} {} {} {} 0", top, -uv, -vx, ux)?;
+                writeln!(out, "{} {} {} {} 0", top, uv, vx, -ux)?;
+            }
+        }
+    }
+    for (weight, lit) in soft.iter() {
+        writeln!(out, "{} {} 0", weight, lit)?;
+    }
+    Ok(())
+}
+
+/// Reads a model of the instance written by `write_wcnf` from the output of a MaxSAT solver and
+/// returns the encoded ordering of `scc`. Both the classic `v 1 -2 3 ...` lines and the binary
+/// `v 101...` line of the MaxSAT Evaluations are accepted, all other lines are ignored.
+pub fn read_model<R: BufRead>(scc: &SCC, reader: R) -> Result<Vec<usize>, ParseError> {
+    let nvars = scc.n * scc.n.saturating_sub(1) / 2;
+    let mut value: Vec<Option<bool>> = vec![None; nvars];
+    let mut lineno: usize = 0;
+    for line in reader.lines() {
+        lineno += 1;
+        let line = line.map_err(|e| ParseError::Io { line: lineno, source: e })?;
+        let ll: Vec<&str> = line.split_whitespace().collect();
+        if ll.is_empty() || ll[0] != "v" {
+            continue;
+        }
+        if ll.len() == 2 && ll[1].len() == nvars && ll[1].bytes().all(|b| b == b'0' || b == b'1') {
+            for (i, b) in ll[1].bytes().enumerate() {
+                value[i] = Some(b == b'1');
+            }
+            continue;
+        }
+        for token in ll[1..].iter() {
+            let (negated, var) = match token.strip_prefix('-') {
+                Some(var) => (true, var),
+                None => (false, *token),
+            };
+            let var = bipartite_graph::parse_number(var, lineno)?;
+            if var == 0 {
+                continue;
+            }
+            if var > nvars {
+                return Err(ParseError::InvalidModel { line: lineno });
+            }
+            value[var-1] = Some(!negated);
+        }
+    }
+    if value.iter().any(|x| x.is_none()) {
+        return Err(ParseError::InvalidModel { line: lineno });
+    }
+    scc.tournament_to_ordering(|u, v| value[scc.pair_index(u, v)] == Some(true)).ok_or(ParseError::InvalidModel { line: lineno })
+}
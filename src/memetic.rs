@@ -0,0 +1,205 @@
+// Memetic algorithm: every SCC keeps an elite pool of locally optimal orderings. While a pool is
+// not full it is filled with hill-climber runs on random insertion orderings; afterwards each
+// generation recombines random members by order crossover or by a Borda merge of three parents
+// (sorting the vertices by their summed positions) and improves the offspring with the
+// hill-climber. An offspring replaces the worst member, or the member closest to it in
+// Kendall-tau distance if it is too close to the pool to add diversity.
+use crate::sifting::{self, eval_ordering_scc};
+use crate::local_search::{Incumbents, SccSearch};
+use crate::strategy::Context;
+use rand::Rng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+
+/// Orderings kept per SCC.
+const POOL_SIZE: usize = 10;
+
+/// Fraction of all pairs an offspring has to order differently from every pool member to replace
+/// the worst one instead of its nearest member.
+const DIVERSITY: f64 = 0.01;
+
+/// Generations without a new incumbent after which a pool keeps only its best member.
+const STAGNATION: usize = 100;
+
+/// Number of pairs ordered differently by the permutations a and b of the same vertices.
+pub fn kendall_tau(a: &[usize], b: &[usize]) -> u64 {
+    let mut pos = vec![0; a.len()];
+    for (i, &v) in a.iter().enumerate() {
+        pos[v] = i;
+    }
+    let mut seq: Vec<usize> = b.iter().map(|&v| pos[v]).collect();
+    let mut buf = vec![0; seq.len()];
+    count_inversions(&mut seq, &mut buf)
+}
+
+// sorts seq by merge sort and returns its number of inversions
+fn count_inversions(seq: &mut [usize], buf: &mut [usize]) -> u64 {
+    let n = seq.len();
+    if n < 2 {
+        return 0;
+    }
+    let mid = n / 2;
+    let mut inversions = count_inversions(&mut seq[..mid], &mut buf[..mid]) + count_inversions(&mut seq[mid..], &mut buf[mid..]);
+    let (mut i, mut j) = (0, mid);
+    for slot in buf[..n].iter_mut() {
+        if j == n || (i < mid && seq[i] <= seq[j]) {
+            *slot = seq[i];
+            i += 1;
+        } else {
+            *slot = seq[j];
+            inversions += (mid - i) as u64;
+            j += 1;
+        }
+    }
+    seq.copy_from_slice(&buf[..n]);
+    inversions
+}
+
+// copies a random segment of p1 and fills the other positions in the order of p2
+fn order_crossover(p1: &[usize], p2: &[usize], rng: &mut StdRng) -> Vec<usize> {
+    let n = p1.len();
+    let a = rng.gen_range(0..n);
+    let b = rng.gen_range(a..=n);
+    let mut taken = vec![false; n];
+    for &v in p1[a..b].iter() {
+        taken[v] = true;
+    }
+    let mut rest = p2.iter().cloned().filter(|&v| !taken[v]);
+    let mut child = Vec::with_capacity(n);
+    for (i, &v) in p1.iter().enumerate() {
+        if a <= i && i < b {
+            child.push(v);
+        } else {
+            child.push(rest.next().unwrap());
+        }
+    }
+    child
+}
+
+// orders the vertices by their total position in the parents, which keeps every pair on which
+// all parents agree
+fn borda_merge(parents: &[&Vec<usize>], rng: &mut StdRng) -> Vec<usize> {
+    let n = parents[0].len();
+    let mut score = vec![0; n];
+    for p in parents.iter() {
+        for (i, &v) in p.iter().enumerate() {
+            score[v] += i;
+        }
+    }
+    let mut child: Vec<usize> = (0..n).collect();
+    child.shuffle(rng);
+    child.sort_by_key(|&v| score[v]);
+    child
+}
+
+#[derive(Default)]
+pub struct Memetic {
+    pool: Vec<Vec<(Vec<usize>, u64)>>,
+    stall: Vec<usize>,
+    restarts: usize,
+}
+
+impl Memetic {
+    // adds the offspring to pool i if it is better than the member it would replace
+    fn insert(&mut self, i: usize, child: Vec<usize>, val: u64) {
+        let pool = &mut self.pool[i];
+        let n = child.len() as u64;
+        let threshold = (DIVERSITY * (n * (n - 1) / 2) as f64) as u64;
+        let (nearest, dist) = (0..pool.len()).map(|k| (k, kendall_tau(&pool[k].0, &child))).min_by_key(|&(_, d)| d).unwrap();
+        if dist == 0 {
+            return;
+        }
+        let replace = if dist < threshold {
+            nearest
+        } else if pool.len() < POOL_SIZE {
+            pool.push((child, val));
+            return;
+        } else {
+            (0..pool.len()).max_by_key(|&k| pool[k].1).unwrap()
+        };
+        if val < pool[replace].1 {
+            pool[replace] = (child, val);
+        }
+    }
+}
+
+impl SccSearch for Memetic {
+    fn name(&self) -> &str {
+        "memetic"
+    }
+
+    fn prepare(&mut self, inc: &mut Incumbents, _inv_w: &[Vec<Vec<u64>>], ctx: &mut Context) {
+        self.pool = (0..inc.sccs.len()).map(|i| vec![(inc.perm[i].clone(), inc.val[i])]).collect();
+        self.stall = vec![0; inc.sccs.len()];
+        ctx.phase("memetic");
+    }
+
+    /// One generation: an offspring for every open SCC, improved by one hill-climber run.
+    fn improve(&mut self, inc: &mut Incumbents, inv_w: &[Vec<Vec<u64>>], open: &[usize], ctx: &mut Context) -> bool {
+        let sccs = &inc.sccs;
+        let mut children = inc.perm.clone();
+        for i in open.iter().cloned() {
+            let pool = &self.pool[i];
+            children[i] = if pool.len() < POOL_SIZE {
+                sifting::insertion_scc(&sccs[i], &inv_w[i], &mut ctx.rng, &ctx.cancel)
+            } else {
+                let parents: Vec<&Vec<usize>> = pool.choose_multiple(&mut ctx.rng, 3).map(|(p, _)| p).collect();
+                if ctx.rng.gen_bool(0.5) {
+                    order_crossover(parents[0], parents[1], &mut ctx.rng)
+                } else {
+                    borda_merge(&parents, &mut ctx.rng)
+                }
+            };
+        }
+        let children = sifting::hillclimber_sifting(sccs, inv_w, children, &mut ctx.rng, &ctx.cancel);
+        if ctx.cancel.is_cancelled() {
+            return false;
+        }
+        for i in open.iter().cloned() {
+            let val = eval_ordering_scc(&children[i], &inc.sccs[i], &ctx.cancel);
+            if inc.offer(i, &children[i], val) {
+                self.stall[i] = 0;
+            } else {
+                self.stall[i] += 1;
+            }
+            self.insert(i, children[i].clone(), val);
+            if self.stall[i] >= STAGNATION {
+                self.restarts += 1;
+                ctx.restart(self.restarts);
+                self.pool[i] = vec![(inc.perm[i].clone(), inc.val[i])];
+                self.stall[i] = 0;
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn kendall_tau_counts_discordant_pairs() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for n in 0..40 {
+            let mut a: Vec<usize> = (0..n).collect();
+            let mut b = a.clone();
+            a.shuffle(&mut rng);
+            b.shuffle(&mut rng);
+            let mut pos = vec![0; n];
+            for (i, &v) in b.iter().enumerate() {
+                pos[v] = i;
+            }
+            let mut expected = 0;
+            for i in 0..n {
+                for j in (i+1)..n {
+                    if pos[a[i]] > pos[a[j]] {
+                        expected += 1;
+                    }
+                }
+            }
+            assert_eq!(kendall_tau(&a, &b), expected);
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use std::fmt;
+use std::time::Duration;
+
+/// Progress of a solve, reported to the observer of a `Solver`. Times are measured from the start
+/// of the solve.
+#[derive(Clone, Debug)]
+pub enum Event {
+    /// The search entered a new phase.
+    Phase { name: String, elapsed: Duration },
+    /// A better ordering was found.
+    Incumbent { crossings: u64, elapsed: Duration },
+    /// The strategy restarted its search from a new initial ordering.
+    Restart { run: usize, elapsed: Duration },
+}
+
+/// Callback receiving the events of a solve.
+pub type Observer<'a> = Box<dyn FnMut(&Event) + 'a>;
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Event::Phase { name, elapsed } => write!(f, "{:.3}s phase {}", elapsed.as_secs_f64(), name),
+            Event::Incumbent { crossings, elapsed } => write!(f, "{:.3}s incumbent {} crossings", elapsed.as_secs_f64(), crossings),
+            Event::Restart { run, elapsed } => write!(f, "{:.3}s restart {}", elapsed.as_secs_f64(), run),
+        }
+    }
+}
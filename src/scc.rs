@@ -1,17 +1,61 @@
 use crate::graph;
 
+#[derive(Clone)]
 pub struct SCC {
     pub n: usize,
     pub labels: Vec<usize>,
     pub w: Vec<Vec<u64>>,
-    pub g: Vec<Vec<usize>> 
+    pub g: Vec<Vec<usize>>,
+    pub optimal: bool, // the identity ordering is known to be optimal
 }
 
 impl SCC {
     pub fn new(labels: Vec<usize>, w: Vec<Vec<u64>>, g: Vec<Vec<usize>>) -> SCC {
-        SCC {n: labels.len(), labels, w, g }
+        SCC {n: labels.len(), labels, w, g, optimal: false }
     }
-    pub fn fas_to_ordering(&self, fas: &Vec<(usize, usize)>) -> Vec<usize> {
+
+    /// Returns the SCC with its vertices renumbered such that vertex i is vertex ordering[i] of
+    /// this SCC.
+    pub fn permuted(&self, ordering: &[usize]) -> SCC {
+        let mut inv = vec![0; self.n];
+        for i in 0..self.n {
+            inv[ordering[i]] = i;
+        }
+        let labels: Vec<usize> = ordering.iter().map(|&v| self.labels[v]).collect();
+        let w: Vec<Vec<u64>> = ordering.iter().map(|&u| ordering.iter().map(|&v| self.w[u][v]).collect()).collect();
+        let g: Vec<Vec<usize>> = ordering.iter().map(|&u| self.g[u].iter().map(|&v| inv[v]).collect()).collect();
+        SCC { n: self.n, labels, w, g, optimal: self.optimal }
+    }
+    /// Index of the pair u < v among all pairs (0, 1), (0, 2), ..., (n-2, n-1), e.g., to number
+    /// the ordering variables of an exported model.
+    pub fn pair_index(&self, u: usize, v: usize) -> usize {
+        u * (2 * self.n - u - 1) / 2 + (v - u - 1)
+    }
+
+    /// Turns a tournament, where before(u, v) for u < v tells whether u precedes v, into an
+    /// ordering. Returns None if the tournament is not transitive.
+    pub fn tournament_to_ordering<F: Fn(usize, usize) -> bool>(&self, before: F) -> Option<Vec<usize>> {
+        let mut predecessors = vec![0; self.n];
+        for u in 0..self.n {
+            for v in (u+1)..self.n {
+                if before(u, v) {
+                    predecessors[v] += 1;
+                } else {
+                    predecessors[u] += 1;
+                }
+            }
+        }
+        let mut ordering: Vec<usize> = (0..self.n).collect();
+        ordering.sort_by_key(|&v| predecessors[v]);
+        for i in 0..self.n {
+            if predecessors[ordering[i]] != i {
+                return None;
+            }
+        }
+        Some(ordering)
+    }
+
+    pub fn fas_to_ordering(&self, fas: &[(usize, usize)]) -> Vec<usize> {
         let mut fas_lookup: Vec<Vec<bool>> = vec![vec![false; self.n]; self.n]; 
         for (u,v) in fas.iter() {
             fas_lookup[*u][*v] = true;
@@ -29,7 +73,7 @@ impl SCC {
         graph::top_ordering(&rest_graph)
     }
     
-    pub fn ordering_to_fas(&self, ordering: &Vec<usize>) -> Vec<(usize, usize)> { 
+    pub fn ordering_to_fas(&self, ordering: &[usize]) -> Vec<(usize, usize)> { 
         let mut fas = Vec::new();
         let mut invorder = vec![0; ordering.len()];
         for i in 0..ordering.len() {
@@ -45,3 +89,25 @@ impl SCC {
         fas 
     }
 }
+
+#[cfg(test)]
+impl SCC {
+    /// An SCC with random weights (not necessarily strongly connected), with the pairwise minima
+    /// subtracted as in a reduced instance if `reduced`.
+    #[allow(clippy::needless_range_loop)]
+    pub(crate) fn random(n: usize, reduced: bool, rng: &mut rand::rngs::StdRng) -> SCC {
+        use rand::Rng;
+        let mut w: Vec<Vec<u64>> = (0..n).map(|u| (0..n).map(|v| if u == v { 0 } else { rng.gen_range(0..6) }).collect()).collect();
+        if reduced {
+            for u in 0..n {
+                for v in (u+1)..n {
+                    let m = std::cmp::min(w[u][v], w[v][u]);
+                    w[u][v] -= m;
+                    w[v][u] -= m;
+                }
+            }
+        }
+        let g = (0..n).map(|u| (0..n).filter(|&v| w[u][v] != 0).collect()).collect();
+        SCC::new((0..n).collect(), w, g)
+    }
+}
@@ -1,20 +1,25 @@
 use crate::scc::SCC;
-use crate::BipartiteGraph;
 use crate::graph;
-use crate::global_abort::GLOBAL_ABORT;
-use std::time::Instant;
-use std::sync::atomic::Ordering;
-use rand::thread_rng;
+use crate::cancel::CancelToken;
+use crate::strategy::{Context, Strategy};
+use crate::subset_dp;
+use crate::lower_bound;
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
 use rand::Rng;
+use std::cmp;
+use std::time::Duration;
 
-fn get_inv_w(sccs: &Vec<SCC>) -> Vec<Vec<Vec<u64>>> {
+#[allow(clippy::needless_range_loop)]
+/// Transposed weight matrices of the SCCs, which the insertion heuristics and the hill-climber
+/// read row by row, or None if `cancel` fires before they are complete.
+pub fn get_inv_w(sccs: &[SCC], cancel: &CancelToken) -> Option<Vec<Vec<Vec<u64>>>> {
     let mut inv_w: Vec<Vec<Vec<u64>>> = Vec::new();
     for scc in sccs {
         let mut scc_inv_w = vec![vec![0; scc.n]; scc.n];
         for j in 0..scc.n {
-            if GLOBAL_ABORT.load(Ordering::Relaxed) {
-                return Vec::new();
+            if cancel.is_cancelled() {
+                return None;
             }
             for k in 0..scc.n {
                 scc_inv_w[j][k] = scc.w[k][j];
@@ -22,13 +27,13 @@ fn get_inv_w(sccs: &Vec<SCC>) -> Vec<Vec<Vec<u64>>> {
         }
         inv_w.push(scc_inv_w);
     }
-    inv_w 
+    Some(inv_w)
 }
 
-pub fn eval_ordering_scc(perm: &Vec<usize>, scc: &SCC) -> u64 {
+pub fn eval_ordering_scc(perm: &[usize], scc: &SCC, cancel: &CancelToken) -> u64 {
     let mut res = 0;
     for i in 0..scc.n {
-        if GLOBAL_ABORT.load(Ordering::Relaxed) {
+        if cancel.is_cancelled() {
             return u64::MAX-1;
         }
         for j in (i+1)..scc.n {
@@ -38,7 +43,8 @@ pub fn eval_ordering_scc(perm: &Vec<usize>, scc: &SCC) -> u64 {
     res
 }
 
-fn map_to_original_labels(perm: &Vec<Vec<usize>>, sccs: &Vec<SCC>) -> Vec<usize> {
+/// Concatenates the per-SCC permutations into an ordering of all vertices (labels of the SCCs).
+pub fn map_to_original_labels(perm: &[Vec<usize>], sccs: &[SCC]) -> Vec<usize> {
     let mut ordering = Vec::new();
     for i in 0..sccs.len() {
         for &p in perm[i].iter() {
@@ -48,14 +54,48 @@ fn map_to_original_labels(perm: &Vec<Vec<usize>>, sccs: &Vec<SCC>) -> Vec<usize>
     ordering
 }
 
-fn insert_cost_per_pos(scc: &SCC, diffs: &Vec<Vec<u64>>, perm: &Vec<usize>, v: usize) -> Vec<u64> {
+/// Splits an ordering of all vertices (labels of the SCCs) into per-SCC permutations. SCCs known
+/// to be optimal keep the identity.
+pub fn split_ordering(ordering: &[usize], sccs: &[SCC]) -> Vec<Vec<usize>> {
+    let mut pos = vec![usize::MAX; sccs.iter().map(|scc| scc.n).sum()];
+    for (i, v) in ordering.iter().cloned().enumerate() {
+        if v < pos.len() {
+            pos[v] = i;
+        }
+    }
+    let mut perm: Vec<Vec<usize>> = Vec::new();
+    for scc in sccs.iter() {
+        let mut scc_perm: Vec<usize> = (0..scc.n).collect();
+        if !scc.optimal {
+            scc_perm.sort_by_key(|&j| pos[scc.labels[j]]);
+        }
+        perm.push(scc_perm);
+    }
+    perm
+}
+
+/// Solves the SCCs with at most `threshold` vertices (capped by `subset_dp::MAX_SIZE`) by the
+/// subset dynamic program and renumbers them such that the identity ordering is optimal.
+pub fn solve_small_sccs(sccs: &mut [SCC], threshold: usize, cancel: &CancelToken) {
+    let threshold = cmp::min(threshold, subset_dp::MAX_SIZE);
+    for scc in sccs.iter_mut() {
+        if scc.n > 1 && scc.n <= threshold {
+            if let Some((ordering, _)) = subset_dp::solve(scc, cancel) {
+                *scc = scc.permuted(&ordering);
+                scc.optimal = true;
+            }
+        }
+    }
+}
+
+pub(crate) fn insert_cost_per_pos(scc: &SCC, diffs: &[Vec<u64>], perm: &[usize], v: usize) -> Vec<u64> {
     let n = perm.len();
     let mut pre: Vec<u64> = vec![0; n+1];
     let mut suf: Vec<u64> = vec![0; n+1];
     for i in 0..n {
         pre[i+1] = pre[i] + scc.w[v][perm[i]];
     }
-    let mut perm_rev = perm.clone();
+    let mut perm_rev = perm.to_vec();
     perm_rev.reverse();
     for i in 0..n {
         suf[i+1] = suf[i] + diffs[v][perm_rev[i]];
@@ -64,7 +104,7 @@ fn insert_cost_per_pos(scc: &SCC, diffs: &Vec<Vec<u64>>, perm: &Vec<usize>, v: u
     pre.iter().zip(&suf).map(|(p, s)| p + s).collect()
 }
 
-fn get_min(val: &Vec<u64>) -> (u64, Vec<usize>) {
+pub(crate) fn get_min(val: &[u64]) -> (u64, Vec<usize>) {
     let minval = *val.iter().min().unwrap();
     let minima = val.iter()
         .enumerate()
@@ -74,52 +114,62 @@ fn get_min(val: &Vec<u64>) -> (u64, Vec<usize>) {
     (minval, minima)
 }
 
-pub fn insertion_sifting(sccs: &Vec<SCC>) -> Vec<Vec<usize>> {
-    let inv_w = get_inv_w(sccs); // could precompute this
+pub fn insertion_sifting(sccs: &[SCC], inv_w: &[Vec<Vec<u64>>], rng: &mut StdRng, cancel: &CancelToken) -> Vec<Vec<usize>> {
     let mut ordering: Vec<Vec<usize>> = Vec::new();
-    let mut rng = thread_rng();
     for i in 0..sccs.len() {
         let scc = &sccs[i];
-        let mut scc_ordering: Vec<usize> = Vec::new();
-        let mut vertices: Vec<usize> = (0..scc.n).collect();
-        vertices.shuffle(&mut rng);
-        for v in vertices.iter().cloned() {
-            if GLOBAL_ABORT.load(Ordering::Relaxed) {
-                scc_ordering.push(v);
-                continue;
-            }
-            let cost = insert_cost_per_pos(scc, &inv_w[i], &scc_ordering, v);
-            let (_, minima) = get_min(&cost);
-            scc_ordering.insert(*minima.choose(&mut rng).unwrap(), v);
+        if scc.optimal {
+            ordering.push((0..scc.n).collect());
+            continue;
         }
-        ordering.push(scc_ordering);
+        ordering.push(insertion_scc(scc, &inv_w[i], rng, cancel));
     }
     ordering
 }
 
-pub fn insertionplus_sifting(sccs: &Vec<SCC>) -> Vec<Vec<usize>> {
-    let inv_w = get_inv_w(sccs); // could precompute this
+/// Inserts the vertices of `scc` in random order, each at a random cheapest position, given the
+/// inverse weights `inv_w` of the SCC (see `get_inv_w`).
+pub fn insertion_scc(scc: &SCC, inv_w: &[Vec<u64>], rng: &mut StdRng, cancel: &CancelToken) -> Vec<usize> {
+    let mut scc_ordering: Vec<usize> = Vec::new();
+    let mut vertices: Vec<usize> = (0..scc.n).collect();
+    vertices.shuffle(rng);
+    for v in vertices.iter().cloned() {
+        if cancel.is_cancelled() {
+            scc_ordering.push(v);
+            continue;
+        }
+        let cost = insert_cost_per_pos(scc, inv_w, &scc_ordering, v);
+        let (_, minima) = get_min(&cost);
+        scc_ordering.insert(*minima.choose(rng).unwrap(), v);
+    }
+    scc_ordering
+}
+
+pub fn insertionplus_sifting(sccs: &[SCC], inv_w: &[Vec<Vec<u64>>], rng: &mut StdRng, cancel: &CancelToken) -> Vec<Vec<usize>> {
     let mut ordering: Vec<Vec<usize>> = Vec::new();
-    let mut rng = thread_rng();
     for i in 0..sccs.len() {
         let scc = &sccs[i];
+        if scc.optimal {
+            ordering.push((0..scc.n).collect());
+            continue;
+        }
         let mut scc_ordering: Vec<usize> = Vec::new();
         let mut vertices: Vec<usize> = (0..scc.n).collect();
-        vertices.shuffle(&mut rng);
+        vertices.shuffle(rng);
         for v in vertices.iter().cloned() {
-            if GLOBAL_ABORT.load(Ordering::Relaxed) {
+            if cancel.is_cancelled() {
                 scc_ordering.push(v);
                 continue;
             }
-            let cost = insert_cost_per_pos(scc, &inv_w[i], &mut scc_ordering, v);
+            let cost = insert_cost_per_pos(scc, &inv_w[i], &scc_ordering, v);
             let (_, minima) = get_min(&cost);
-            scc_ordering.insert(*minima.choose(&mut rng).unwrap(), v);
-            if scc_ordering.len() % 50 == 0 {
+            scc_ordering.insert(*minima.choose(rng).unwrap(), v);
+            if scc_ordering.len().is_multiple_of(50) {
                 // put into function
                 let mut iter = 0;
                 let mut last_improvement = 0;
                 while iter - last_improvement < 2*scc_ordering.len() { 
-                    if GLOBAL_ABORT.load(Ordering::Relaxed) {
+                    if cancel.is_cancelled() {
                         break;
                     }
                     let vpos = rng.gen_range(0..scc_ordering.len());
@@ -134,7 +184,7 @@ pub fn insertionplus_sifting(sccs: &Vec<SCC>) -> Vec<Vec<usize>> {
                     }
                     let mut inspos;
                     loop {
-                        inspos = *minima.choose(&mut rng).unwrap();
+                        inspos = *minima.choose(rng).unwrap();
                         if inspos !=  vpos || minima.len() == 1 {
                             break;
                         }
@@ -151,22 +201,20 @@ pub fn insertionplus_sifting(sccs: &Vec<SCC>) -> Vec<Vec<usize>> {
     
 }
 
-pub fn hillclimber_sifting(sccs: &Vec<SCC>, initial_ordering: Vec<Vec<usize>>) -> Vec<Vec<usize>> {
-    let inv_w = get_inv_w(sccs); // could precompute this
+pub fn hillclimber_sifting(sccs: &[SCC], inv_w: &[Vec<Vec<u64>>], initial_ordering: Vec<Vec<usize>>, rng: &mut StdRng, cancel: &CancelToken) -> Vec<Vec<usize>> {
     let mut ordering = initial_ordering;
-    let mut rng = thread_rng();
     let mut iter = 0;
     let mut last_improvement = 0;
     while iter - last_improvement < 4 { 
         for i in 0..sccs.len() {
             let scc = &sccs[i];
-            if scc.n == 1 { continue; }
+            if scc.n == 1 || scc.optimal { continue; }
             let scc_ordering = &mut ordering[i];            
             // do shuffles or just take random elements?
             let mut vertices: Vec<usize> = (0..scc.n).collect();
-            vertices.shuffle(&mut rng);
+            vertices.shuffle(rng);
             for v in vertices.iter().cloned() {
-                if GLOBAL_ABORT.load(Ordering::Relaxed) {
+                if cancel.is_cancelled() {
                     return ordering;
                 }
                 // for now always remove element first, later optimize this
@@ -181,7 +229,7 @@ pub fn hillclimber_sifting(sccs: &Vec<SCC>, initial_ordering: Vec<Vec<usize>>) -
                 }
                 let mut inspos;
                 loop {
-                    inspos = *minima.choose(&mut rng).unwrap();
+                    inspos = *minima.choose(rng).unwrap();
                     if inspos !=  vpos || minima.len() == 1 {
                         break;
                     }
@@ -194,127 +242,356 @@ pub fn hillclimber_sifting(sccs: &Vec<SCC>, initial_ordering: Vec<Vec<usize>>) -
     ordering
 }
 
-pub fn sifting_heuristic(_g: &BipartiteGraph, sccs: &Vec<SCC>) -> Vec<usize> {
-    let start = Instant::now();
-    // PART 1: run heuristic a few times on original instance
-    // initialize frequency count
-    let mut freqs_per_scc = Vec::new();
-    for scc in sccs.iter() {
-        freqs_per_scc.push(vec![vec![0; scc.n]; scc.n]);
+/// Walks from `start` towards `guide` by moving the vertex guide[k] to position k for k = 0, 1,
+/// ... and returns the cheapest ordering strictly between the two (`start` if there is none).
+pub fn path_relinking(scc: &SCC, inv_w: &[Vec<u64>], start: &[usize], guide: &[usize], cancel: &CancelToken) -> Vec<usize> {
+    let mut ordering = start.to_vec();
+    let mut val = eval_ordering_scc(start, scc, cancel);
+    let mut best: Option<(u64, Vec<usize>)> = None;
+    for k in 0..guide.len() {
+        if cancel.is_cancelled() {
+            break;
+        }
+        let v = guide[k];
+        // the first k positions already agree with guide, so v is behind them
+        let vpos = ordering.iter().position(|&x| x == v).unwrap();
+        if vpos == k {
+            continue;
+        }
+        ordering.remove(vpos);
+        let cost = insert_cost_per_pos(scc, inv_w, &ordering, v);
+        val = val - cost[vpos] + cost[k];
+        ordering.insert(k, v);
+        if ordering[k+1..] == guide[k+1..] {
+            break;
+        }
+        if best.as_ref().is_none_or(|(bestval, _)| val < *bestval) {
+            best = Some((val, ordering.clone()));
+        }
     }
+    best.map_or_else(|| start.to_vec(), |(_, ordering)| ordering)
+}
 
-    let mut first_bestval_perscc = vec![u64::MAX; sccs.len()];
-    let mut first_bestperm_perscc = vec![Vec::new(); sccs.len()]; 
-    let mut cntruns = 0;
-    while start.elapsed().as_secs_f64() <= 60.0 { 
-        let perm = hillclimber_sifting(sccs, insertion_sifting(sccs));
-        if GLOBAL_ABORT.load(Ordering::Relaxed) {
-            if first_bestval_perscc[0] == u64::MAX {
-                return map_to_original_labels(&perm, sccs);
-                
-            } else {
-                return map_to_original_labels(&first_bestperm_perscc, sccs);
+enum Phase {
+    First,
+    FirstOnly,
+    Second,
+}
+
+/// The heuristic for graphs of moderate size. Each SCC of the reduced instance is ordered by
+/// repeatedly running the hill-climber on random insertion orderings. After a first phase (a fifth
+/// of the time limit, 60 seconds of the PACE limit if none is given) arcs that rarely point
+/// forward are dropped, the SCCs are recomputed and the search continues on the smaller SCCs.
+/// SCCs with at most `Context::dp_threshold` vertices are solved exactly upfront and skipped by
+/// the search. In the second phase every `RELINK_EVERY`th run relinks the best ordering of each
+/// SCC with another elite ordering instead of starting from a new insertion ordering.
+pub struct Sifting {
+    phase: Phase,
+    phase1: f64,
+    sccs: Vec<SCC>,
+    offset: u64,
+    bound_perscc: Vec<u64>,
+    initial: Option<Vec<Vec<usize>>>,
+    freqs_per_scc: Vec<Vec<Vec<usize>>>,
+    first_bestval_perscc: Vec<u64>,
+    first_bestperm_perscc: Vec<Vec<usize>>,
+    cntruns: usize,
+    runs: usize,
+    newsccs: Vec<SCC>,
+    bestval_perscc: Vec<u64>,
+    bestperm_perscc: Vec<Vec<usize>>,
+    elite_perscc: Vec<Vec<(Vec<usize>, u64)>>,
+    // inverse weights of sccs in the first phase and of newsccs in the second
+    inv_w_perscc: Option<Vec<Vec<Vec<u64>>>>,
+}
+
+/// Distinct orderings kept per thinned SCC for path relinking.
+const ELITE_SIZE: usize = 5;
+
+/// Every how many runs of the second phase relink elite orderings.
+const RELINK_EVERY: usize = 4;
+
+impl Sifting {
+    pub fn new() -> Sifting {
+        Sifting {
+            phase: Phase::First,
+            phase1: 60.0,
+            sccs: Vec::new(),
+            offset: 0,
+            bound_perscc: Vec::new(),
+            initial: None,
+            freqs_per_scc: Vec::new(),
+            first_bestval_perscc: Vec::new(),
+            first_bestperm_perscc: Vec::new(),
+            cntruns: 0,
+            runs: 0,
+            newsccs: Vec::new(),
+            bestval_perscc: Vec::new(),
+            bestperm_perscc: Vec::new(),
+            elite_perscc: Vec::new(),
+            inv_w_perscc: None,
+        }
+    }
+
+    // improves the bounds of the sccs by the lp relaxation, using the first incumbents as upper
+    // bounds and at most a tenth of the first phase
+    fn lp_bounds(&mut self, cancel: &CancelToken) {
+        let cancel = cancel.child();
+        cancel.cancel_after(Duration::from_secs_f64(self.phase1 / 10.0));
+        lower_bound::raise_by_lp(&self.sccs, &self.first_bestval_perscc, &mut self.bound_perscc, &cancel);
+    }
+
+    // PART 1: run heuristic a few times on original instance
+    #[allow(clippy::needless_range_loop)]
+    fn improve_first(&mut self, ctx: &mut Context) -> bool {
+        self.runs += 1;
+        ctx.restart(self.runs);
+        let sccs = &self.sccs;
+        let cancel = &ctx.cancel;
+        let perm = match (self.initial.take(), &self.inv_w_perscc) {
+            (Some(perm), Some(inv_w)) => hillclimber_sifting(sccs, inv_w, perm, &mut ctx.rng, cancel),
+            (None, Some(inv_w)) => hillclimber_sifting(sccs, inv_w, insertion_sifting(sccs, inv_w, &mut ctx.rng, cancel), &mut ctx.rng, cancel),
+            // cancelled while computing the inverse weights
+            (initial, None) => initial.unwrap_or_else(|| sccs.iter().map(|scc| (0..scc.n).collect()).collect()),
+        };
+        if cancel.is_cancelled() {
+            if self.first_bestval_perscc.first() == Some(&u64::MAX) {
+                self.first_bestperm_perscc = perm;
             }
+            return false;
         }
         for i in 0..sccs.len() {
-            let scc_eval = eval_ordering_scc(&perm[i], &sccs[i]);
-            if scc_eval < first_bestval_perscc[i] {
-                first_bestval_perscc[i] = scc_eval;
-                first_bestperm_perscc[i] = perm[i].clone();
+            let scc_eval = eval_ordering_scc(&perm[i], &sccs[i], cancel);
+            if scc_eval < self.first_bestval_perscc[i] {
+                self.first_bestval_perscc[i] = scc_eval;
+                self.first_bestperm_perscc[i] = perm[i].clone();
+            }
+        }
+        if self.runs == 1 {
+            self.lp_bounds(cancel);
+        }
+        // sccs whose incumbent meets their lower bound are not searched any further
+        for i in 0..self.sccs.len() {
+            if !self.sccs[i].optimal && self.first_bestval_perscc[i] == self.bound_perscc[i] {
+                self.sccs[i] = self.sccs[i].permuted(&self.first_bestperm_perscc[i]);
+                self.sccs[i].optimal = true;
+                self.first_bestperm_perscc[i] = (0..self.sccs[i].n).collect();
             }
         }
+        if self.sccs.iter().all(|scc| scc.optimal) {
+            return false;
+        }
+        let sccs = &self.sccs;
+        if let Phase::FirstOnly = self.phase {
+            return true;
+        }
         for i in 0..sccs.len() {
             for j in 0..perm[i].len() {
-                if GLOBAL_ABORT.load(Ordering::Relaxed) {
-                    return map_to_original_labels(&first_bestperm_perscc, sccs);
+                if cancel.is_cancelled() {
+                    return false;
                 }
                 for k in (j+1)..perm[i].len() {
-                    freqs_per_scc[i][perm[i][j]][perm[i][k]] += 1;
+                    self.freqs_per_scc[i][perm[i][j]][perm[i][k]] += 1;
                 }
             }
         }
-        cntruns += 1;
-    }
-
-    if cntruns < 10 { // should rarely happen
-        while !GLOBAL_ABORT.load(Ordering::Relaxed) { 
-            let perm = hillclimber_sifting(sccs, insertion_sifting(sccs));
-            if GLOBAL_ABORT.load(Ordering::Relaxed) {
-                if first_bestval_perscc[0] == u64::MAX {
-                    return map_to_original_labels(&perm, sccs);
-                    
-                } else {
-                    return map_to_original_labels(&first_bestperm_perscc, sccs);
-                }
-            }
-            for i in 0..sccs.len() {
-                let scc_eval = eval_ordering_scc(&perm[i], &sccs[i]);
-                if scc_eval < first_bestval_perscc[i] {
-                    first_bestval_perscc[i] = scc_eval;
-                    first_bestperm_perscc[i] = perm[i].clone();
-                }
+        self.cntruns += 1;
+        if ctx.start.elapsed().as_secs_f64() > self.phase1 {
+            if self.cntruns < 10 { // should rarely happen
+                self.phase = Phase::FirstOnly;
+            } else {
+                self.start_second(cancel);
+                ctx.phase("sifting on thinned sccs");
             }
         }
-    } 
-    let mut bestval_perscc = Vec::new(); 
-    let mut bestperm_perscc = Vec::new(); 
-    let mut newsccs: Vec<SCC> = Vec::new();
+        true
+    }
+
     // PART 2: reduce edges which always incur costs and recompute sccs
     // remove edges -> maybe have lower bound on number of iterations
-    // this can never run at timeout 5 min -> no need to insert breaks/returns
-    for i in 0..sccs.len() {
-        let scc = &sccs[i];
-        let mut h = vec![Vec::new(); scc.n];
-        for j in 0..scc.n {
-            for k in scc.g[j].iter().cloned() {
-                if freqs_per_scc[i][j][k] > cntruns / 30 {
-                    h[j].push(k);
+    #[allow(clippy::needless_range_loop)]
+    fn start_second(&mut self, cancel: &CancelToken) {
+        let sccs = &self.sccs;
+        for i in 0..sccs.len() {
+            let scc = &sccs[i];
+            if scc.optimal {
+                // thinning could destroy the optimal ordering, so keep the scc as is
+                self.newsccs.push(scc.clone());
+                let bestnewperm: Vec<usize> = (0..scc.n).collect();
+                self.bestval_perscc.push(eval_ordering_scc(&bestnewperm, scc, cancel));
+                self.bestperm_perscc.push(bestnewperm);
+                continue;
+            }
+            let mut h = vec![Vec::new(); scc.n];
+            for j in 0..scc.n {
+                for k in scc.g[j].iter().cloned() {
+                    if self.freqs_per_scc[i][j][k] > self.cntruns / 30 {
+                        h[j].push(k);
+                    }
                 }
             }
-        }
 
-        let mut invbestperm = vec![0; scc.n];
-        for j in 0..scc.n {
-            invbestperm[first_bestperm_perscc[i][j]] = j;
-        }
-
-        let hsccs = graph::compute_sccs(&h);
-        for hscc in hsccs.iter() {
-            let mut w: Vec<Vec<u64>> = vec![vec![0; hscc.len()]; hscc.len()];
-            let mut g: Vec<Vec<usize>> = vec![Vec::new(); hscc.len()];
-            for j in 0..hscc.len() {
-                for k in 0..hscc.len() {
-                    w[j][k] = scc.w[hscc[j]][hscc[k]];
-                    if w[j][k] != 0 {
-                        g[j].push(k);
+            let mut invbestperm = vec![0; scc.n];
+            for j in 0..scc.n {
+                invbestperm[self.first_bestperm_perscc[i][j]] = j;
+            }
+
+            let hsccs = graph::compute_sccs(&h);
+            for hscc in hsccs.iter() {
+                let mut w: Vec<Vec<u64>> = vec![vec![0; hscc.len()]; hscc.len()];
+                let mut g: Vec<Vec<usize>> = vec![Vec::new(); hscc.len()];
+                for j in 0..hscc.len() {
+                    for k in 0..hscc.len() {
+                        w[j][k] = scc.w[hscc[j]][hscc[k]];
+                        if w[j][k] != 0 {
+                            g[j].push(k);
+                        }
                     }
                 }
+                let mut labels: Vec<usize> = Vec::new();
+                for hl in hscc.iter().cloned() {
+                    labels.push(scc.labels[hl]);
+                }
+                self.newsccs.push(SCC::new(labels, w, g)); 
+
+                let mut bestnewperm: Vec<usize> = (0..hscc.len()).collect();
+                bestnewperm.sort_by_key(|&i| invbestperm[hscc[i]]);
+                self.bestval_perscc.push(eval_ordering_scc(&bestnewperm, self.newsccs.last().unwrap(), cancel));
+                self.bestperm_perscc.push(bestnewperm);
             }
-            let mut labels: Vec<usize> = Vec::new();
-            for hl in hscc.iter().cloned() {
-                labels.push(scc.labels[hl]);
+        }
+        self.elite_perscc = (0..self.newsccs.len()).map(|i| vec![(self.bestperm_perscc[i].clone(), self.bestval_perscc[i])]).collect();
+        self.inv_w_perscc = get_inv_w(&self.newsccs, cancel);
+        self.phase = Phase::Second;
+    }
+
+    // path relinking from the best ordering of each scc towards a random other elite ordering
+    #[allow(clippy::needless_range_loop)]
+    fn relink(&self, inv_w: &[Vec<Vec<u64>>], rng: &mut StdRng, cancel: &CancelToken) -> Vec<Vec<usize>> {
+        let mut perm: Vec<Vec<usize>> = Vec::new();
+        for i in 0..self.newsccs.len() {
+            let start = &self.bestperm_perscc[i];
+            let guides: Vec<&Vec<usize>> = self.elite_perscc[i].iter().map(|(p, _)| p).filter(|p| *p != start).collect();
+            match guides.choose(rng) {
+                Some(guide) if !self.newsccs[i].optimal => perm.push(path_relinking(&self.newsccs[i], &inv_w[i], start, guide, cancel)),
+                _ => perm.push(start.clone()),
             }
-            newsccs.push(SCC::new(labels, w, g)); 
+        }
+        perm
+    }
 
-            let mut bestnewperm: Vec<usize> = (0..hscc.len()).collect();
-            bestnewperm.sort_by_key(|&i| invbestperm[hscc[i]]);
-            bestval_perscc.push(eval_ordering_scc(&bestnewperm, newsccs.last().unwrap()));
-            bestperm_perscc.push(bestnewperm);
+    // keeps ordering in the elite of scc i if it is new and better than the worst member
+    fn update_elite(&mut self, i: usize, ordering: &Vec<usize>, val: u64) {
+        let elite = &mut self.elite_perscc[i];
+        if elite.iter().any(|(p, _)| p == ordering) {
+            return;
+        }
+        if elite.len() < ELITE_SIZE {
+            elite.push((ordering.clone(), val));
+            return;
+        }
+        let worst = (0..elite.len()).max_by_key(|&k| elite[k].1).unwrap();
+        if val < elite[worst].1 {
+            elite[worst] = (ordering.clone(), val);
         }
     }
 
-    while !GLOBAL_ABORT.load(Ordering::Relaxed) {
-        let perm = hillclimber_sifting(&newsccs, insertionplus_sifting(&newsccs));
-        if GLOBAL_ABORT.load(Ordering::Relaxed) {
-            break;
+    #[allow(clippy::needless_range_loop)]
+    fn improve_second(&mut self, ctx: &mut Context) -> bool {
+        self.runs += 1;
+        ctx.restart(self.runs);
+        let cancel = &ctx.cancel;
+        let inv_w = match self.inv_w_perscc {
+            Some(ref inv_w) => inv_w,
+            None => return false,
+        };
+        let start = if self.runs.is_multiple_of(RELINK_EVERY) {
+            self.relink(inv_w, &mut ctx.rng, cancel)
+        } else {
+            insertionplus_sifting(&self.newsccs, inv_w, &mut ctx.rng, cancel)
+        };
+        let perm = hillclimber_sifting(&self.newsccs, inv_w, start, &mut ctx.rng, cancel);
+        if cancel.is_cancelled() {
+            return false;
         }
-        for i in 0..newsccs.len() {
-            let scc_eval = eval_ordering_scc(&perm[i], &newsccs[i]);
-            if scc_eval < bestval_perscc[i] {
-                bestval_perscc[i] = scc_eval;
-                bestperm_perscc[i] = perm[i].clone();
+        let mut improved = false;
+        for i in 0..self.newsccs.len() {
+            let scc_eval = eval_ordering_scc(&perm[i], &self.newsccs[i], cancel);
+            self.update_elite(i, &perm[i], scc_eval);
+            if scc_eval < self.bestval_perscc[i] {
+                self.bestval_perscc[i] = scc_eval;
+                self.bestperm_perscc[i] = perm[i].clone();
+                improved = true;
             }
         }
+        if !improved {
+            return true;
+        }
+        // the thinned sccs miss the dropped arcs, so the incumbent is evaluated on the original sccs
+        let perm = split_ordering(&self.best(), &self.sccs);
+        let mut total = 0;
+        for i in 0..self.sccs.len() {
+            total += eval_ordering_scc(&perm[i], &self.sccs[i], cancel);
+        }
+        total > self.bound_perscc.iter().sum::<u64>()
+    }
+}
+
+impl Default for Sifting {
+    fn default() -> Sifting {
+        Sifting::new()
+    }
+}
+
+impl Strategy for Sifting {
+    fn name(&self) -> &str {
+        "sifting"
+    }
+
+    fn prepare(&mut self, ctx: &mut Context) {
+        let (mut sccs, offset) = ctx.reduce();
+        self.phase1 = ctx.time_limit.map_or(60.0, |limit| limit.as_secs_f64() / 5.0);
+        self.offset = offset;
+        solve_small_sccs(&mut sccs, ctx.dp_threshold, &ctx.cancel);
+        // the bounds of the remaining sccs may use a tenth of the first phase
+        let bound_cancel = ctx.cancel.child();
+        bound_cancel.cancel_after(Duration::from_secs_f64(self.phase1 / 10.0));
+        self.bound_perscc = lower_bound::scc_bounds(&sccs, &bound_cancel);
+        // initialize frequency count
+        for scc in sccs.iter() {
+            self.freqs_per_scc.push(vec![vec![0; scc.n]; scc.n]);
+        }
+        self.first_bestval_perscc = vec![u64::MAX; sccs.len()];
+        self.first_bestperm_perscc = vec![Vec::new(); sccs.len()];
+        // a warm start is the first incumbent and the starting point of the first hill-climber run
+        if let Some(ref ordering) = ctx.initial {
+            let perm = split_ordering(ordering, &sccs);
+            for i in 0..sccs.len() {
+                self.first_bestval_perscc[i] = eval_ordering_scc(&perm[i], &sccs[i], &ctx.cancel);
+                self.first_bestperm_perscc[i] = perm[i].clone();
+            }
+            self.initial = Some(perm);
+        }
+        self.inv_w_perscc = get_inv_w(&sccs, &ctx.cancel);
+        self.sccs = sccs;
+        ctx.phase("sifting on sccs");
+    }
+
+    fn improve(&mut self, ctx: &mut Context) -> bool {
+        match self.phase {
+            Phase::Second => self.improve_second(ctx),
+            _ => self.improve_first(ctx),
+        }
+    }
+
+    fn best(&self) -> Vec<usize> {
+        match self.phase {
+            Phase::Second => map_to_original_labels(&self.bestperm_perscc, &self.newsccs),
+            _ => map_to_original_labels(&self.first_bestperm_perscc, &self.sccs),
+        }
+    }
+
+    fn lower_bound(&self) -> u64 {
+        self.offset + self.bound_perscc.iter().sum::<u64>()
     }
-    map_to_original_labels(&bestperm_perscc, &newsccs)
 }
@@ -1,12 +1,12 @@
 use crate::heuristic;
 use crate::BipartiteGraph;
-use crate::global_abort::GLOBAL_ABORT;
-use std::sync::atomic::Ordering;
+use crate::cancel::CancelToken;
+use crate::strategy::{Context, Strategy};
 use rand::seq::SliceRandom;
 use rand::Rng;
 
 #[inline(always)]
-fn get_crossings(g: &BipartiteGraph, cm: &mut Vec<Vec<u8>>, u: usize, v: usize) -> u64 {
+fn get_crossings(g: &BipartiteGraph, cm: &mut [Vec<u8>], u: usize, v: usize) -> u64 {
     if cm[u][v] == 255 {
         let x = g.pair_crossing_number(u, v);
         if x < 255 {
@@ -18,13 +18,13 @@ fn get_crossings(g: &BipartiteGraph, cm: &mut Vec<Vec<u8>>, u: usize, v: usize)
 }
 
 #[inline(always)]
-fn update_perm_pos(perm: &mut Vec<usize>, pos: &mut Vec<usize>, swap: usize) {
+fn update_perm_pos(perm: &mut [usize], pos: &mut [usize], swap: usize) {
     perm.swap(swap, swap+1);
     pos[perm[swap]] = swap;
     pos[perm[swap+1]] = swap+1;
 }
 
-fn best_reinsert(g: &BipartiteGraph, perm: &Vec<usize>, cm: &mut Vec<Vec<u8>>, v: usize, up: usize, range: usize) -> (i64, usize) {
+fn best_reinsert(g: &BipartiteGraph, perm: &[usize], cm: &mut [Vec<u8>], v: usize, up: usize, range: usize, cancel: &CancelToken) -> (i64, usize) {
     let mut minval: i64 = i64::MAX; 
     let mut minidx: usize = 0;
     let mut acc: i64 = 0;
@@ -32,7 +32,7 @@ fn best_reinsert(g: &BipartiteGraph, perm: &Vec<usize>, cm: &mut Vec<Vec<u8>>, v
     let mut steps_since_min = 0;
     let num_steps = if up == 1 { perm.len() - v - 1 } else { v };
     for step in 0..num_steps {
-        if GLOBAL_ABORT.load(Ordering::Relaxed) {
+        if cancel.is_cancelled() {
             return (minval, minidx);
         }
         let i = if up == 1 { v + step + 1 } else { v - step - 1 };
@@ -57,34 +57,74 @@ fn best_reinsert(g: &BipartiteGraph, perm: &Vec<usize>, cm: &mut Vec<Vec<u8>>, v
     (minval, minidx)
 }
 
-pub fn sifting_large(g: &BipartiteGraph) -> Vec<usize> {
-    let mut rng = rand::thread_rng();
-    let mut perm: Vec<usize> = heuristic::mean_heuristic(g); 
-    let mut iter = 0;
+/// Sifting on the full graph for large instances. Crossing numbers of pairs are computed on
+/// demand and cached in a byte matrix. It starts from the warm start if given and from the
+/// mean heuristic otherwise; each round is one sweep reinserting all vertices in random order.
+pub struct SiftingLarge {
+    perm: Vec<usize>,
+    pos: Vec<usize>,
+    cm: Vec<Vec<u8>>,
+    iter: usize,
+}
+
+impl SiftingLarge {
+    pub fn new() -> SiftingLarge {
+        SiftingLarge {
+            perm: Vec::new(),
+            pos: Vec::new(),
+            cm: Vec::new(),
+            iter: 0,
+        }
+    }
+}
+
+impl Default for SiftingLarge {
+    fn default() -> SiftingLarge {
+        SiftingLarge::new()
+    }
+}
 
-    let mut pos = vec![0; perm.len()];
-    for i in 0..perm.len() {
-        pos[perm[i]] = i;
+impl Strategy for SiftingLarge {
+    fn name(&self) -> &str {
+        "sifting-large"
     }
-    
-    let mut cm: Vec<Vec<u8>> = vec![vec![255; perm.len()]; perm.len()];
 
-    loop {
+    fn needs_graph(&self) -> bool {
+        true
+    }
+
+    fn prepare(&mut self, ctx: &mut Context) {
+        self.perm = match ctx.initial {
+            Some(ref ordering) => ordering.clone(),
+            None => heuristic::mean_heuristic(ctx.graph().expect("needs a graph")),
+        };
+        self.pos = vec![0; self.perm.len()];
+        for i in 0..self.perm.len() {
+            self.pos[self.perm[i]] = i;
+        }
+        self.cm = vec![vec![255; self.perm.len()]; self.perm.len()];
+    }
+
+    fn improve(&mut self, ctx: &mut Context) -> bool {
+        let g = ctx.graph().expect("needs a graph");
+        let cancel = &ctx.cancel;
+        let perm = &mut self.perm;
+        let pos = &mut self.pos;
         let mut vertices: Vec<usize> = (0..perm.len()).collect();
-        vertices.shuffle(&mut rng);
+        vertices.shuffle(&mut ctx.rng);
         for vert in vertices.iter().cloned() {
-            if GLOBAL_ABORT.load(Ordering::Relaxed) {
-                return perm;
+            if cancel.is_cancelled() {
+                return false;
             }
             let v = pos[vert];
-            let range = iter % 3;
-            let (minval_up, minidx_up) = best_reinsert(g, &perm, &mut cm, v, 1, range);
-            if GLOBAL_ABORT.load(Ordering::Relaxed) {
-                return perm;
+            let range = self.iter % 3;
+            let (minval_up, minidx_up) = best_reinsert(g, perm, &mut self.cm, v, 1, range, cancel);
+            if cancel.is_cancelled() {
+                return false;
             }
-            let (minval_down, minidx_down) = best_reinsert(g, &perm, &mut cm, v, 0, range);
-            if GLOBAL_ABORT.load(Ordering::Relaxed) {
-                return perm;
+            let (minval_down, minidx_down) = best_reinsert(g, perm, &mut self.cm, v, 0, range, cancel);
+            if cancel.is_cancelled() {
+                return false;
             }
 
             let minval;
@@ -97,7 +137,7 @@ pub fn sifting_large(g: &BipartiteGraph) -> Vec<usize> {
                 minidx = minidx_down;
             } else {
                 minval = minval_up;
-                if rng.gen_range(0..=1) == 0 {
+                if ctx.rng.gen_range(0..=1) == 0 {
                     minidx = minidx_up;
                 } else {
                     minidx = minidx_down;
@@ -107,16 +147,20 @@ pub fn sifting_large(g: &BipartiteGraph) -> Vec<usize> {
             if minval <= 0 {
                 if minidx > v {
                     for i in v..minidx {
-                        update_perm_pos(&mut perm, &mut pos, i);
+                        update_perm_pos(perm, pos, i);
                     }
                 } else {
                     for i in (minidx..v).rev() {
-                        update_perm_pos(&mut perm, &mut pos, i);
+                        update_perm_pos(perm, pos, i);
                     } 
                 }
             }
         }
-        iter += 1;
+        self.iter += 1;
+        true
     }
-}
 
+    fn best(&self) -> Vec<usize> {
+        self.perm.clone()
+    }
+}
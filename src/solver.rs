@@ -0,0 +1,234 @@
+use crate::BipartiteGraph;
+use crate::scc::SCC;
+use crate::cancel::CancelToken;
+use crate::observer::{Event, Observer};
+use crate::sifting::{self, Sifting};
+use crate::strategy::{self, Context, Instance, Strategy};
+use crate::subset_dp;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use std::time::{Duration, Instant};
+
+/// Result of a solve.
+#[derive(Clone, Debug)]
+pub struct Solution {
+    /// Free vertices in input ids (n0+1, ..., n0+n1), as written to a PACE solution file, or the
+    /// labels of the vertices of a reduced instance (see `Solver::reduced`).
+    pub ordering: Vec<usize>,
+    /// Number of crossings, or the cost of the ordering for a reduced instance.
+    pub crossings: u64,
+    pub lower_bound: u64,
+    pub strategy_used: String,
+    /// Number of hill-climber runs (or sweeps for the large strategies).
+    pub runs: usize,
+    pub elapsed: Duration,
+    pub proved_optimal: bool,
+}
+
+impl Solution {
+    /// Difference between the number of crossings and the lower bound.
+    pub fn gap(&self) -> u64 {
+        self.crossings.saturating_sub(self.lower_bound)
+    }
+
+    /// The gap relative to the number of crossings, in percent.
+    pub fn relative_gap(&self) -> f64 {
+        if self.crossings == 0 {
+            0.0
+        } else {
+            100.0 * self.gap() as f64 / self.crossings as f64
+        }
+    }
+}
+
+impl Instance<'_> {
+    // the ordering reported to the caller for an ordering of a strategy
+    fn output(&self, best: &[usize]) -> Vec<usize> {
+        match self {
+            Instance::Graph(g) => g.input_ordering(best),
+            Instance::Reduced(..) => best.to_vec(),
+        }
+    }
+
+    // the cost of an ordering returned by `output`
+    fn cost(&self, ordering: &[usize]) -> u64 {
+        match self {
+            Instance::Graph(g) => g.count_crossings(ordering),
+            Instance::Reduced(sccs, offset) => {
+                let perm = sifting::split_ordering(ordering, sccs);
+                let cancel = CancelToken::new();
+                offset + (0..sccs.len()).map(|i| sifting::eval_ordering_scc(&perm[i], &sccs[i], &cancel)).sum::<u64>()
+            }
+        }
+    }
+}
+
+/// Configures and runs a solve of one instance.
+pub struct Solver<'a> {
+    instance: Instance<'a>,
+    strategy: Option<Box<dyn Strategy>>,
+    time_limit: Option<Duration>,
+    seed: Option<u64>,
+    initial: Option<Vec<usize>>,
+    cancel: CancelToken,
+    dp_threshold: usize,
+    observer: Option<Observer<'a>>,
+}
+
+impl<'a> Solver<'a> {
+    pub fn new(g: &'a BipartiteGraph) -> Solver<'a> {
+        Solver::with_instance(Instance::Graph(g))
+    }
+
+    /// A solve of a linear ordering instance that is already reduced, e.g., by
+    /// `LinearOrdering::reduce`. Orderings are orderings of the labels of `sccs`.
+    pub fn reduced(sccs: Vec<SCC>, offset: u64) -> Solver<'a> {
+        Solver::with_instance(Instance::Reduced(sccs, offset))
+    }
+
+    fn with_instance(instance: Instance<'a>) -> Solver<'a> {
+        Solver { instance, strategy: None, time_limit: None, seed: None, initial: None, cancel: CancelToken::new(), dp_threshold: subset_dp::DEFAULT_THRESHOLD, observer: None }
+    }
+
+    /// Uses the given strategy (see `Registry`) instead of the default for the instance. A
+    /// strategy that needs a graph (see `Strategy::needs_graph`) is ignored for reduced instances.
+    pub fn strategy(mut self, strategy: Box<dyn Strategy>) -> Solver<'a> {
+        self.strategy = Some(strategy);
+        self
+    }
+
+    /// Returns once `limit` has elapsed. Without a time limit the solve runs until cancelled.
+    pub fn time_limit(mut self, limit: Duration) -> Solver<'a> {
+        self.time_limit = Some(limit);
+        self
+    }
+
+    /// Seeds all random choices. Without a seed, the generator is seeded from entropy.
+    pub fn seed(mut self, seed: u64) -> Solver<'a> {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Warm-starts every strategy from an ordering of the free vertices in input ids (of the
+    /// labels for a reduced instance).
+    pub fn initial(mut self, ordering: &[usize]) -> Solver<'a> {
+        self.initial = Some(ordering.to_vec());
+        self
+    }
+
+    /// SCCs with at most `n` vertices are solved exactly by dynamic programming over subsets
+    /// (up to `subset_dp::MAX_SIZE`). Zero disables the dynamic program.
+    pub fn dp_threshold(mut self, n: usize) -> Solver<'a> {
+        self.dp_threshold = n;
+        self
+    }
+
+    pub fn cancel_token(mut self, cancel: CancelToken) -> Solver<'a> {
+        self.cancel = cancel;
+        self
+    }
+
+    /// Reports the progress of the solve to `observer`, e.g., to print it or to forward it to a
+    /// channel. New incumbents are detected after each round of the strategy.
+    pub fn observer<F: FnMut(&Event) + 'a>(mut self, observer: F) -> Solver<'a> {
+        self.observer = Some(Box::new(observer));
+        self
+    }
+
+    pub fn solve(self) -> Solution {
+        let start = Instant::now();
+        let rng = match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        // the time limit only applies to this solve, not to the caller's token
+        let cancel = self.cancel.child();
+        if let Some(limit) = self.time_limit {
+            cancel.cancel_after(limit);
+        }
+        let initial = match self.instance {
+            Instance::Graph(g) => self.initial.as_deref().map(|ord| g.reduced_ordering(ord)),
+            Instance::Reduced(..) => self.initial,
+        };
+        let mut strategy = match (self.strategy, &self.instance) {
+            (Some(strategy), Instance::Graph(_)) => strategy,
+            (Some(strategy), Instance::Reduced(..)) if !strategy.needs_graph() => strategy,
+            (_, Instance::Graph(g)) => strategy::default_for(g),
+            (_, Instance::Reduced(..)) => Box::new(Sifting::new()),
+        };
+        let mut ctx = Context { instance: self.instance, initial, time_limit: self.time_limit, start, rng, cancel, dp_threshold: self.dp_threshold, observer: self.observer };
+
+        ctx.phase("prepare");
+        strategy.prepare(&mut ctx);
+        ctx.phase("search");
+        let mut runs: usize = 0;
+        let mut incumbent = u64::MAX;
+        loop {
+            let more = strategy.improve(&mut ctx);
+            if ctx.cancel.is_cancelled() {
+                break;
+            }
+            runs += 1;
+            if ctx.observer.is_some() {
+                let crossings = ctx.instance.cost(&ctx.instance.output(&strategy.best()));
+                if crossings < incumbent {
+                    incumbent = crossings;
+                    let elapsed = start.elapsed();
+                    ctx.emit(Event::Incumbent { crossings, elapsed });
+                }
+            }
+            if !more {
+                break;
+            }
+        }
+
+        let ordering = ctx.instance.output(&strategy.best());
+        let crossings = ctx.instance.cost(&ordering);
+        let mut lower_bound = strategy.lower_bound();
+        let mut proved_optimal = strategy.proved_optimal();
+        if proved_optimal {
+            lower_bound = crossings;
+        } else if crossings == lower_bound {
+            proved_optimal = true;
+        }
+        Solution { ordering, crossings, lower_bound, strategy_used: strategy.name().to_string(), runs, elapsed: start.elapsed(), proved_optimal }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy::Registry;
+    use rand::Rng;
+
+    // random graph with n0 fixed and n1 free vertices of degree 3
+    fn random_graph(n0: usize, n1: usize, seed: u64) -> BipartiteGraph {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut lines = vec![format!("p ocr {} {} {}", n0, n1, 3 * n1)];
+        for v in 0..n1 {
+            for _ in 0..3 {
+                lines.push(format!("{} {}", rng.gen_range(1..=n0), n0 + v + 1));
+            }
+        }
+        BipartiteGraph::from_reader(lines.join("\n").as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn cancelled_solves_return_an_ordering() {
+        let (n0, n1) = (30, 60);
+        let g = random_graph(n0, n1, 1);
+        let registry = Registry::default();
+        for name in registry.names() {
+            for limit in [Duration::ZERO, Duration::from_millis(1)] {
+                let sol = Solver::new(&g).strategy(registry.create(name).unwrap()).time_limit(limit).seed(0).dp_threshold(2).solve();
+                let mut ordering = sol.ordering.clone();
+                ordering.sort();
+                assert_eq!(ordering, ((n0 + 1)..=(n0 + n1)).collect::<Vec<usize>>(), "{}", name);
+            }
+            let cancel = CancelToken::new();
+            cancel.cancel();
+            let sol = Solver::new(&g).strategy(registry.create(name).unwrap()).cancel_token(cancel).solve();
+            assert_eq!(sol.ordering.len(), n1, "{}", name);
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use crate::BipartiteGraph;
+use crate::scc::SCC;
+use crate::cancel::CancelToken;
+use crate::observer::{Event, Observer};
+use crate::annealing::Annealing;
+use crate::cutwidth_dp::CutwidthDp;
+use crate::exact::Exact;
+use crate::ils::IteratedLocalSearch;
+use crate::local_search::LocalSearch;
+use crate::memetic::Memetic;
+use crate::sifting::Sifting;
+use crate::sifting_large::SiftingLarge;
+use crate::sifting_very_large::SiftingVeryLarge;
+use crate::tabu::TabuSearch;
+use rand::rngs::StdRng;
+use std::time::{Duration, Instant};
+
+/// The instance a strategy works on.
+pub enum Instance<'a> {
+    /// A one-sided crossing minimization instance. Orderings are orderings of the twin classes
+    /// 0, ..., n1-1 of the graph.
+    Graph(&'a BipartiteGraph),
+    /// A linear ordering instance that is already reduced (see `linear_ordering::reduce_matrix`),
+    /// given by its SCCs and the cost every ordering incurs. Orderings are orderings of the labels
+    /// of the SCCs.
+    Reduced(Vec<SCC>, u64),
+}
+
+/// Everything a strategy may use while searching.
+pub struct Context<'a> {
+    pub instance: Instance<'a>,
+    /// Ordering to warm-start from, if any.
+    pub initial: Option<Vec<usize>>,
+    pub time_limit: Option<Duration>,
+    pub start: Instant,
+    pub rng: StdRng,
+    pub cancel: CancelToken,
+    /// SCCs up to this size may be solved exactly by `subset_dp`.
+    pub dp_threshold: usize,
+    pub observer: Option<Observer<'a>>,
+}
+
+impl<'a> Context<'a> {
+    /// The graph, unless the instance is a reduced linear ordering instance.
+    pub fn graph(&self) -> Option<&'a BipartiteGraph> {
+        match self.instance {
+            Instance::Graph(g) => Some(g),
+            Instance::Reduced(..) => None,
+        }
+    }
+
+    /// The SCCs of the reduced instance and the cost every ordering incurs (see
+    /// `BipartiteGraph::reduce_with_offset`).
+    pub fn reduce(&self) -> (Vec<SCC>, u64) {
+        match self.instance {
+            Instance::Graph(g) => g.reduce_with_offset(),
+            Instance::Reduced(ref sccs, offset) => (sccs.clone(), offset),
+        }
+    }
+
+    pub fn emit(&mut self, event: Event) {
+        if let Some(ref mut observer) = self.observer {
+            observer(&event);
+        }
+    }
+
+    /// Reports that the search entered the phase `name`.
+    pub fn phase(&mut self, name: &str) {
+        if self.observer.is_some() {
+            let elapsed = self.start.elapsed();
+            self.emit(Event::Phase { name: name.to_string(), elapsed });
+        }
+    }
+
+    /// Reports that the search restarted for the `run`-th time.
+    pub fn restart(&mut self, run: usize) {
+        if self.observer.is_some() {
+            let elapsed = self.start.elapsed();
+            self.emit(Event::Restart { run, elapsed });
+        }
+    }
+}
+
+/// A search strategy. The solver calls `prepare` once and then `improve` until the strategy
+/// returns false or the solve is cancelled; afterwards `best` is reported.
+pub trait Strategy {
+    fn name(&self) -> &str;
+
+    /// Sets up the search, e.g., computes an initial ordering.
+    fn prepare(&mut self, ctx: &mut Context);
+
+    /// Runs one round of the search (one hill-climber run, one sweep, ...). Returns false if
+    /// further rounds cannot improve the result.
+    fn improve(&mut self, ctx: &mut Context) -> bool;
+
+    /// The best ordering found so far.
+    fn best(&self) -> Vec<usize>;
+
+    /// A lower bound on the number of crossings of any ordering, if the strategy knows one.
+    fn lower_bound(&self) -> u64 {
+        0
+    }
+
+    /// Whether `best` is known to be optimal.
+    fn proved_optimal(&self) -> bool {
+        false
+    }
+
+    /// Whether the strategy works on the graph itself rather than on the reduced instance, so
+    /// that it cannot solve `Instance::Reduced`.
+    fn needs_graph(&self) -> bool {
+        false
+    }
+}
+
+type Factory = Box<dyn Fn() -> Box<dyn Strategy>>;
+
+/// Strategies selectable by name, e.g., from the command line.
+pub struct Registry {
+    entries: Vec<(String, Factory)>,
+}
+
+impl Registry {
+    /// A registry without any strategies.
+    pub fn empty() -> Registry {
+        Registry { entries: Vec::new() }
+    }
+
+    /// Registers a strategy under `name`, replacing any strategy of the same name.
+    pub fn register<F: Fn() -> Box<dyn Strategy> + 'static>(&mut self, name: &str, factory: F) {
+        self.entries.retain(|(n, _)| n != name);
+        self.entries.push((name.to_string(), Box::new(factory)));
+    }
+
+    pub fn create(&self, name: &str) -> Option<Box<dyn Strategy>> {
+        self.entries.iter().find(|(n, _)| n == name).map(|(_, factory)| factory())
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.entries.iter().map(|(n, _)| n.as_str()).collect()
+    }
+}
+
+impl Default for Registry {
+    /// The strategies shipped with sisyphus.
+    fn default() -> Registry {
+        let mut registry = Registry::empty();
+        registry.register("annealing", || Box::new(LocalSearch::<Annealing>::new()));
+        registry.register("cutwidth-dp", || Box::new(CutwidthDp::new()));
+        registry.register("exact", || Box::new(Exact::new()));
+        registry.register("ils", || Box::new(LocalSearch::<IteratedLocalSearch>::new()));
+        registry.register("memetic", || Box::new(LocalSearch::<Memetic>::new()));
+        registry.register("sifting", || Box::new(Sifting::new()));
+        registry.register("sifting-large", || Box::new(SiftingLarge::new()));
+        registry.register("sifting-very-large", || Box::new(SiftingVeryLarge::new()));
+        registry.register("tabu", || Box::new(LocalSearch::<TabuSearch>::new()));
+        registry
+    }
+}
+
+/// The sifting strategy used for graphs with `n1` (twin-reduced) free vertices.
+pub fn for_size(n1: usize) -> Box<dyn Strategy> {
+    // encode matrix/graph more efficiently (u8) and allow for larger graphs
+    if n1 < 10_000 {
+        Box::new(Sifting::new())
+    } else if n1 < 75_000 {
+        Box::new(SiftingLarge::new())
+    } else {
+        Box::new(SiftingVeryLarge::new())
+    }
+}
+
+/// The strategy used if none is chosen: the exact dynamic program for instances of the
+/// parameterized track, otherwise one of the sifting strategies depending on the size.
+pub fn default_for(g: &BipartiteGraph) -> Box<dyn Strategy> {
+    if g.cutwidth.is_some() {
+        Box::new(CutwidthDp::new())
+    } else {
+        for_size(g.n1)
+    }
+}
@@ -0,0 +1,69 @@
+// Held-Karp style dynamic program over subsets for small SCCs. dp[S] is the minimum cost of
+// ordering the vertices of S as a prefix; appending v to S costs the sum of w[v][u] over u in S.
+// These sums are looked up in two tables over the lower and upper half of the vertices, so the
+// program runs in O(2^n * n) time and O(2^n) space.
+use crate::scc::SCC;
+use crate::cancel::CancelToken;
+
+/// SCCs up to this size are solved by the dynamic program unless configured otherwise.
+pub const DEFAULT_THRESHOLD: usize = 20;
+
+/// Largest SCC the dynamic program accepts (its tables take about 9 * 2^n bytes).
+pub const MAX_SIZE: usize = 26;
+
+// sums[v][m] = sum of w[v][offset + b] over the bits b of m
+#[allow(clippy::needless_range_loop)]
+fn half_sums(scc: &SCC, offset: usize, size: usize) -> Vec<Vec<u64>> {
+    let mut sums = vec![vec![0; 1 << size]; scc.n];
+    for v in 0..scc.n {
+        for m in 1..(1usize << size) {
+            let b = m.trailing_zeros() as usize;
+            sums[v][m] = sums[v][m & (m - 1)] + scc.w[v][offset + b];
+        }
+    }
+    sums
+}
+
+/// Returns an optimal ordering of `scc` and its cost, or None if the SCC has more than `MAX_SIZE`
+/// vertices or `cancel` interrupted the computation.
+pub fn solve(scc: &SCC, cancel: &CancelToken) -> Option<(Vec<usize>, u64)> {
+    let n = scc.n;
+    if n > MAX_SIZE {
+        return None;
+    }
+    let h = n / 2;
+    let lowmask = (1usize << h) - 1;
+    let lo = half_sums(scc, 0, h);
+    let hi = half_sums(scc, h, n - h);
+    let full = (1usize << n) - 1;
+    let mut dp: Vec<u64> = vec![u64::MAX; 1 << n];
+    let mut last: Vec<u8> = vec![0; 1 << n];
+    dp[0] = 0;
+    for s in 0..full {
+        if s & 0xfff == 0 && cancel.is_cancelled() {
+            return None;
+        }
+        let d = dp[s];
+        let (ls, hs) = (s & lowmask, s >> h);
+        let mut rest = full & !s;
+        while rest != 0 {
+            let v = rest.trailing_zeros() as usize;
+            rest &= rest - 1;
+            let t = s | (1 << v);
+            let c = d + lo[v][ls] + hi[v][hs];
+            if c < dp[t] {
+                dp[t] = c;
+                last[t] = v as u8;
+            }
+        }
+    }
+    let mut ordering: Vec<usize> = Vec::with_capacity(n);
+    let mut s = full;
+    while s != 0 {
+        let v = last[s] as usize;
+        ordering.push(v);
+        s ^= 1 << v;
+    }
+    ordering.reverse();
+    Some((ordering, dp[full]))
+}
@@ -0,0 +1,120 @@
+// Tabu search over the reinsertion moves of the hill-climber. Every step evaluates the best
+// reinsertion of a few random vertices (see `sifting::insert_cost_per_pos`) and applies the best
+// of them even if it is worse, so the search walks across plateaus instead of cycling among equal
+// positions. A vertex may not return to a position it left within the tenure unless the move
+// yields a new incumbent (aspiration).
+use crate::sifting::insert_cost_per_pos;
+use crate::local_search::{Incumbents, SccSearch};
+use crate::strategy::Context;
+use rand::Rng;
+use std::collections::HashMap;
+
+/// Vertices whose reinsertions are evaluated per step.
+const CANDIDATES: usize = 32;
+
+/// Sweeps without a new incumbent after which an SCC returns to its incumbent.
+const STAGNATION: usize = 50;
+
+#[derive(Default)]
+pub struct TabuSearch {
+    // (vertex, position) -> step until which the vertex may not be inserted at the position
+    tabu: Vec<HashMap<(usize, usize), usize>>,
+    step: Vec<usize>,
+    stall: Vec<usize>,
+}
+
+impl TabuSearch {
+    fn tenure(n: usize) -> usize {
+        usize::max(7, n / 20)
+    }
+}
+
+impl SccSearch for TabuSearch {
+    fn name(&self) -> &str {
+        "tabu"
+    }
+
+    fn prepare(&mut self, inc: &mut Incumbents, _inv_w: &[Vec<Vec<u64>>], ctx: &mut Context) {
+        self.tabu = vec![HashMap::new(); inc.sccs.len()];
+        self.step = vec![0; inc.sccs.len()];
+        self.stall = vec![0; inc.sccs.len()];
+        ctx.phase("tabu search");
+    }
+
+    /// n steps in every open SCC; an SCC without a new incumbent for a while returns to it.
+    fn improve(&mut self, inc: &mut Incumbents, inv_w: &[Vec<Vec<u64>>], open: &[usize], ctx: &mut Context) -> bool {
+        for i in open.iter().cloned() {
+            let n = inc.sccs[i].n;
+            let tenure = TabuSearch::tenure(n);
+            let mut improved = false;
+            for _ in 0..n {
+                if ctx.cancel.is_cancelled() {
+                    return false;
+                }
+                let perm = &mut inc.current[i];
+                let step = self.step[i];
+                // best admissible move (new cost, vertex, old position, new position) with
+                // ties broken uniformly at random
+                let mut best: Option<(u64, usize, usize, usize)> = None;
+                let mut ties = 0;
+                for _ in 0..usize::min(CANDIDATES, n) {
+                    let vpos = ctx.rng.gen_range(0..perm.len());
+                    let v = perm.remove(vpos);
+                    let cost = insert_cost_per_pos(&inc.sccs[i], &inv_w[i], perm, v);
+                    for q in 0..cost.len() {
+                        if q == vpos {
+                            continue;
+                        }
+                        let val = inc.current_val[i] - cost[vpos] + cost[q];
+                        let tabu = self.tabu[i].get(&(v, q)).is_some_and(|&until| until > step);
+                        if tabu && val >= inc.val[i] {
+                            continue;
+                        }
+                        match best {
+                            Some((bval, ..)) if val > bval => {}
+                            Some((bval, ..)) if val == bval => {
+                                ties += 1;
+                                if ctx.rng.gen_range(0..ties) == 0 {
+                                    best = Some((val, v, vpos, q));
+                                }
+                            }
+                            _ => {
+                                best = Some((val, v, vpos, q));
+                                ties = 1;
+                            }
+                        }
+                    }
+                    perm.insert(vpos, v);
+                }
+                self.step[i] += 1;
+                let (val, v, vpos, q) = match best {
+                    Some(best) => best,
+                    None => continue,
+                };
+                perm.remove(vpos);
+                perm.insert(q, v);
+                inc.current_val[i] = val;
+                self.tabu[i].insert((v, vpos), step + tenure + ctx.rng.gen_range(0..=tenure / 2));
+                if inc.accept(i) {
+                    improved = true;
+                    if !inc.is_open(i) {
+                        break;
+                    }
+                }
+            }
+            let step = self.step[i];
+            self.tabu[i].retain(|_, until| *until > step);
+            if improved {
+                self.stall[i] = 0;
+            } else {
+                self.stall[i] += 1;
+                if self.stall[i] >= STAGNATION {
+                    inc.reset(i);
+                    self.tabu[i].clear();
+                    self.stall[i] = 0;
+                }
+            }
+        }
+        true
+    }
+}